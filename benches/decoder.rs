@@ -27,6 +27,23 @@ fn create_struct(c: &mut Criterion) {
     });
 }
 
+// Compare building a decoder per use (eg per message) against re-using the shared default instance
+fn create_per_use_vs_shared_default(c: &mut Criterion) {
+    c.bench_function("Create struct per decode", |b| {
+        b.iter(|| {
+            let decoder = EscapeSequence::new(Separators::default());
+            let _ = decoder.decode(get_sample_message_with_escape_sequences());
+        })
+    });
+
+    c.bench_function("Shared default struct per decode", |b| {
+        b.iter(|| {
+            let decoder = EscapeSequence::default();
+            let _ = decoder.decode(get_sample_message_with_escape_sequences());
+        })
+    });
+}
+
 fn no_sequences_but_backslash(c: &mut Criterion) {
     c.bench_function("No Escape Sequences But Backslash", |b| {
         let delims = Separators::default();
@@ -67,6 +84,7 @@ fn get_sample_message_with_escape_sequences() -> &'static str {
 criterion_group!(
     decoder,
     create_struct,
+    create_per_use_vs_shared_default,
     no_sequences,
     no_sequences_but_backslash,
     has_escape_sequences
@@ -19,7 +19,7 @@ fn get_segments_by_name(c: &mut Criterion) {
         let m = Message::try_from(get_sample_message()).unwrap();
 
         b.iter(|| {
-            let _segs = m.segments_by_identifier("OBR").unwrap();
+            let _segs = m.segments_named("OBR");
             //assert!(segs.len() == 1);
         })
     });
@@ -48,6 +48,82 @@ fn get_pid_and_read_field_via_query(c: &mut Criterion) {
     });
 }
 
+fn query_component_joined_vs_parts(c: &mut Criterion) {
+    let m = Message::try_from(get_sample_message()).unwrap();
+    let pid = &m.segments[1];
+    let mut group = c.benchmark_group("Query component from PID");
+
+    group.bench_function("re-joined path (previous)", |b| {
+        b.iter(|| {
+            let indices = ["PID", "F5", "R1", "C2"];
+            let query = indices[1..].join(".");
+            let _val = pid.query(&*query);
+            assert_eq!(_val, "EVE");
+        })
+    });
+
+    group.bench_function("path parts", |b| {
+        b.iter(|| {
+            let indices = ["PID", "F5", "R1", "C2"];
+            let _val = pid.query_parts(&indices[1..]);
+            assert_eq!(_val, "EVE");
+        })
+    });
+
+    group.finish();
+}
+
+const PID_FIELD_PATHS: [&str; 20] = [
+    "PID.F1", "PID.F2", "PID.F3", "PID.F4", "PID.F5", "PID.F6", "PID.F7", "PID.F8", "PID.F9",
+    "PID.F10", "PID.F11", "PID.F12", "PID.F13", "PID.F14", "PID.F15", "PID.F16", "PID.F17",
+    "PID.F18", "PID.F19", "PID.F20",
+];
+
+// None of these fields contain escape sequences, so decoding them should cost little over the query itself
+fn query_vs_query_decoded(c: &mut Criterion) {
+    let m = Message::try_from(get_sample_message()).unwrap();
+    let mut group = c.benchmark_group("Query 20 escape-free PID fields");
+
+    group.bench_function("query", |b| {
+        b.iter(|| {
+            for path in PID_FIELD_PATHS.iter() {
+                let _val = m.query(*path);
+            }
+        })
+    });
+
+    group.bench_function("query_decoded", |b| {
+        b.iter(|| {
+            for path in PID_FIELD_PATHS.iter() {
+                let _val = m.query_decoded(*path);
+            }
+        })
+    });
+
+    group.finish();
+}
+
+fn query_vs_query_many(c: &mut Criterion) {
+    let m = Message::try_from(get_sample_message()).unwrap();
+    let mut group = c.benchmark_group("Query 20 PID fields");
+
+    group.bench_function("individual query calls", |b| {
+        b.iter(|| {
+            for path in PID_FIELD_PATHS.iter() {
+                let _val = m.query(*path);
+            }
+        })
+    });
+
+    group.bench_function("query_many", |b| {
+        b.iter(|| {
+            let _vals = m.query_many(&PID_FIELD_PATHS);
+        })
+    });
+
+    group.finish();
+}
+
 #[cfg(feature = "string_index")]
 fn get_pid_and_read_field_via_index(c: &mut Criterion) {
     c.bench_function("Read Field from PID (index)", |b| {
@@ -67,6 +143,9 @@ criterion_group!(
     get_segments_by_name,
     get_pid_and_read_field_via_vec,
     get_pid_and_read_field_via_query,
+    query_component_joined_vs_parts,
+    query_vs_query_decoded,
+    query_vs_query_many,
     get_pid_and_read_field_via_index
 );
 
@@ -76,6 +155,9 @@ criterion_group!(
     message_parse,
     get_segments_by_name,
     get_pid_and_read_field_via_vec,
-    get_pid_and_read_field_via_query
+    get_pid_and_read_field_via_query,
+    query_component_joined_vs_parts,
+    query_vs_query_decoded,
+    query_vs_query_many
 );
 criterion_main!(benches);
@@ -25,6 +25,10 @@ fn main() -> Result<(), Box<dyn Error>> {
     let charge_to_practice = decoder.decode(charge_to_practice); // Handle the escape sequences
     assert_eq!(charge_to_practice, "Joes Obs & Gynae"); // converted the \T\ sequence to an ampersand
 
+    // Or query and decode in a single call
+    let charge_to_practice = message.query_decoded("OBR.F23");
+    assert_eq!(charge_to_practice, "Joes Obs & Gynae");
+
     Ok(())
 }
 
@@ -2,7 +2,7 @@
  A short example demonstrating one way to use this library for HL7 processing.
 */
 
-use rusthl7::{Field, Hl7ParseError, Message, Separators};
+use rusthl7::{Field, Hl7ParseError, Message, Segment, SegmentKind, Separators};
 use std::{convert::TryFrom, error::Error, fmt::Display};
 
 /// The most important Segment, almost all HL7 messages have an MSH (MLLP simple ack I'm looking at you).
@@ -29,7 +29,7 @@ pub struct MshSegment<'a> {
     pub msh_15_accept_acknowledgment_type: Option<Field<'a>>,
     pub msh_16_application_acknowledgment_type: Option<Field<'a>>,
     pub msh_17_country_code: Option<Field<'a>>,
-    pub msh_18_character_set: Option<Field<'a>>, //TODO: repeating field
+    pub msh_18_character_set: Option<Field<'a>>, // repeating field, see `character_sets()`
     pub msh_19_principal_language_of_message: Option<Field<'a>>,
     // pub msh_20_alternate_character_set_handling_scheme: Option<Field<'a>>,
     // pub msh_21_message_profile_identifier: Option<Vec<Field<'a>>>,
@@ -48,7 +48,8 @@ impl<'a> MshSegment<'a> {
 
         let mut fields = input.split(delims.field);
 
-        assert!(fields.next().unwrap() == "MSH");
+        // some systems send lower case identifiers
+        assert!(fields.next().unwrap().eq_ignore_ascii_case("MSH"));
 
         let _ = fields.next(); //consume the delimiter chars
 
@@ -77,6 +78,18 @@ impl<'a> MshSegment<'a> {
 
         Ok(msh)
     }
+
+    /// MSH-18 may list multiple character sets separated by the repeat char, the first being the primary character set.
+    pub fn character_sets(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.msh_18_character_set
+            .iter()
+            .flat_map(|f| f.repeats.iter().copied())
+    }
+
+    /// The primary (first) character set declared in MSH-18, if any
+    pub fn primary_character_set(&self) -> Option<&'a str> {
+        self.character_sets().next()
+    }
 }
 /// Common formatter trait implementation for the strongly-typed segment
 impl<'a> Display for MshSegment<'a> {
@@ -85,6 +98,12 @@ impl<'a> Display for MshSegment<'a> {
         write!(f, "{}", self.source)
     }
 }
+/// Allows the typed segment to be passed to APIs taking `impl AsRef<str>`
+impl<'a> AsRef<str> for MshSegment<'a> {
+    fn as_ref(&self) -> &str {
+        self.source
+    }
+}
 /// Common clone trait implementation for the strongly-typed segment
 impl<'a> Clone for MshSegment<'a> {
     /// Creates a new Message object using _the same source_ slice as the original.
@@ -94,12 +113,65 @@ impl<'a> Clone for MshSegment<'a> {
     }
 }
 
-/// Extracts header element for external use
-pub fn msh<'a>(msg: &Message<'a>) -> Result<MshSegment<'a>, Hl7ParseError> {
-    let seg = msg.segments_by_identifier("MSH").unwrap()[0];
-    let segment =
-        MshSegment::parse(seg.source, &msg.get_separators()).expect("Failed to parse MSH segment");
-    Ok(segment)
+/// Extracts the header from a parsed message, returning an error if there's no MSH
+impl<'a> TryFrom<&Message<'a>> for MshSegment<'a> {
+    type Error = Hl7ParseError;
+
+    fn try_from(msg: &Message<'a>) -> Result<Self, Self::Error> {
+        let seg = msg
+            .segments_named("MSH")
+            .into_iter()
+            .next()
+            .ok_or(Hl7ParseError::MissingRequiredValue())?;
+        MshSegment::parse(seg.source, &msg.get_separators())
+    }
+}
+
+/// Patient visit details, heavily used in ADT processing.  Unlike the MSH above only the commonly used fields are typed,
+/// the rest are still available via the generic `Segment`.
+#[derive(Debug, PartialEq)]
+pub struct Pv1Segment<'a> {
+    pub source: &'a str,
+    pub pv1_2_patient_class: Field<'a>,
+    pub pv1_3_assigned_patient_location: Option<Field<'a>>,
+    pub pv1_7_attending_doctor: Option<Field<'a>>,
+    pub pv1_19_visit_number: Option<Field<'a>>,
+    pub pv1_44_admit_datetime: Option<Field<'a>>, // DTM, left as a raw field
+}
+
+impl<'a> Pv1Segment<'a> {
+    pub fn parse<S: Into<&'a str>>(
+        input: S,
+        delims: &Separators,
+    ) -> Result<Pv1Segment<'a>, Hl7ParseError> {
+        let input = input.into();
+
+        let fields: Vec<&str> = input.split(delims.field).collect();
+
+        assert!(fields[0].eq_ignore_ascii_case("PV1"));
+
+        // trailing fields may not have been sent at all
+        let field = |idx: usize| fields.get(idx).copied();
+
+        let pv1 = Pv1Segment {
+            source: input,
+            pv1_2_patient_class: Field::parse_mandatory(field(2), delims)?,
+            pv1_3_assigned_patient_location: Field::parse_optional(field(3), delims)?,
+            pv1_7_attending_doctor: Field::parse_optional(field(7), delims)?,
+            pv1_19_visit_number: Field::parse_optional(field(19), delims)?,
+            pv1_44_admit_datetime: Field::parse_optional(field(44), delims)?,
+        };
+
+        Ok(pv1)
+    }
+}
+
+/// Common formatter trait implementation for the strongly-typed segment
+impl<'a> Display for Pv1Segment<'a> {
+    /// Required for to_string() and other formatter consumers
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source)
+    }
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
@@ -111,9 +183,43 @@ fn main() -> Result<(), Box<dyn Error>> {
     let message = Message::try_from(hl7_string)?;
 
     // Get a strongly-typed segment from generic data
-    let header = msh(&message).expect("Failed to extract MSH");
+    let header = MshSegment::try_from(&message)?;
+    assert_eq!(header.as_ref(), message.segments[0].as_ref());
     let send_fac = header.msh_4_sending_facility.unwrap().source;
     assert_eq!(send_fac, message.segments[0].fields[3].source);
+    assert_eq!(send_fac, "ELAB-3");
+
+    // MSH-18 is a repeating field
+    let header = MshSegment::parse(
+        "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4||||||ASCII~8859/1",
+        &message.get_separators(),
+    )?;
+    let charsets: Vec<&str> = header.character_sets().collect();
+    assert_eq!(charsets, vec!["ASCII", "8859/1"]);
+    assert_eq!(header.primary_character_set(), Some("ASCII"));
+
+    // Dispatching on the segment kind is case-insensitive, so a lower case header still gets the typed treatment
+    let lower = Segment::parse(
+        "msh|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4",
+        &message.get_separators(),
+    )?;
+    assert_eq!(lower.kind(), SegmentKind::MSH);
+    let header = MshSegment::parse(lower.source, &message.get_separators())?;
+    assert_eq!(header.msh_10_message_control_id.source, "CNTRL-3456");
+
+    // A typed view over the patient visit
+    let visit = Pv1Segment::parse(
+        "PV1|1|I|2000^2012^01||||004777^ATTEND^AARON^A|||SUR||||ADM|A0||||V1294|||||||||||||||||||||||||200202150930",
+        &message.get_separators(),
+    )?;
+    assert_eq!(visit.pv1_2_patient_class.source, "I");
+    assert_eq!(
+        visit.pv1_3_assigned_patient_location.unwrap()[(0, 1)],
+        "2012"
+    );
+    assert_eq!(visit.pv1_7_attending_doctor.unwrap()[(0, 1)], "ATTEND");
+    assert_eq!(visit.pv1_19_visit_number.unwrap().source, "V1294");
+    assert_eq!(visit.pv1_44_admit_datetime.unwrap().source, "200202150930");
 
     Ok(())
 }
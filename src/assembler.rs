@@ -0,0 +1,170 @@
+use crate::{Hl7ParseError, Message};
+
+/// Re-assembles a logical message that has been split across several transmissions (continuation messages).
+///
+/// Each fragment is expected to carry a sequence number in MSH-13, and every fragment after the first should have
+/// it's continuation pointer (MSH-14) set to the message control id (MSH-10) of the fragment before it.
+/// A fragment that is to be continued ends with a `DSC` segment.
+///
+/// Fragments may be added in any order, [`MessageAssembler::assemble()`] orders them by MSH-13, checks the chain of
+/// continuation pointers is unbroken, and returns the HL7 for the single logical message (using the first fragment's MSH),
+/// ready to be parsed with `Message::try_from()`.
+/// ## Example:
+/// ```
+/// # use rusthl7::Hl7ParseError;
+/// # use rusthl7::{Message, MessageAssembler};
+/// # use std::convert::TryFrom;
+/// # fn main() -> Result<(), Hl7ParseError> {
+/// let second = Message::try_from("MSH|^~\\&|LAB|FAC|HIS|FAC|20210101||ADT^A08|CTRL-2|P|2.4|2|CTRL-1\rNK1|1|SMITH")?;
+/// let first = Message::try_from("MSH|^~\\&|LAB|FAC|HIS|FAC|20210101||ADT^A08|CTRL-1|P|2.4|1\rPID|||555-44-4444\rDSC|CTRL-1")?;
+///
+/// let mut assembler = MessageAssembler::new();
+/// assembler.add(second);
+/// assembler.add(first);
+/// let hl7 = assembler.assemble()?;
+///
+/// let m = Message::try_from(hl7.as_str())?;
+/// assert_eq!(m.segment_names(), vec!["MSH", "PID", "NK1"]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct MessageAssembler<'a> {
+    fragments: Vec<Message<'a>>,
+}
+
+impl<'a> MessageAssembler<'a> {
+    /// Create a new, empty assembler
+    pub fn new() -> MessageAssembler<'a> {
+        MessageAssembler {
+            fragments: Vec::new(),
+        }
+    }
+
+    /// Adds a fragment to be assembled, fragments can be added in any order.
+    pub fn add(&mut self, fragment: Message<'a>) {
+        self.fragments.push(fragment);
+    }
+
+    /// Orders and validates the fragments, returning the HL7 for the assembled message.
+    /// Returns an error if there are no fragments, a fragment is missing a valid MSH-13 sequence number,
+    /// the continuation pointers don't link up, or the final fragment is still expecting a continuation.
+    pub fn assemble(self) -> Result<String, Hl7ParseError> {
+        if self.fragments.is_empty() {
+            return Err(Hl7ParseError::Generic(
+                "No message fragments to assemble".to_string(),
+            ));
+        }
+
+        let mut sequenced = Vec::with_capacity(self.fragments.len());
+        for fragment in self.fragments {
+            let sequence = msh_field(&fragment, 13).parse::<u64>().map_err(|_| {
+                Hl7ParseError::Generic(format!(
+                    "Fragment '{}' has no valid sequence number (MSH-13)",
+                    msh_field(&fragment, 10)
+                ))
+            })?;
+            sequenced.push((sequence, fragment));
+        }
+        sequenced.sort_by_key(|(sequence, _)| *sequence);
+
+        for pair in sequenced.windows(2) {
+            let (previous, next) = (&pair[0].1, &pair[1].1);
+            if msh_field(next, 14) != msh_field(previous, 10) {
+                return Err(Hl7ParseError::Generic(format!(
+                    "Fragment '{}' does not continue from fragment '{}' (MSH-14)",
+                    msh_field(next, 10),
+                    msh_field(previous, 10)
+                )));
+            }
+        }
+
+        let last = &sequenced[sequenced.len() - 1].1;
+        if last
+            .segments
+            .iter()
+            .any(|s| s.identifier().eq_ignore_ascii_case("DSC"))
+        {
+            return Err(Hl7ParseError::Generic(format!(
+                "Final fragment '{}' is expecting a continuation that was not provided",
+                msh_field(last, 10)
+            )));
+        }
+
+        let first = &sequenced[0].1;
+        let separator = first.detected_line_ending().as_str();
+        let body = sequenced.iter().flat_map(|(_, fragment)| {
+            fragment
+                .segments
+                .iter()
+                .filter(|s| !s.is_msh() && !s.identifier().eq_ignore_ascii_case("DSC"))
+        });
+
+        let segments: Vec<&str> = std::iter::once(first.segments[0].source)
+            .chain(body.map(|s| s.source))
+            .collect();
+
+        Ok(segments.join(separator))
+    }
+}
+
+/// Gets the value of MSH-`n` from the given message, or an empty string if it wasn't sent
+fn msh_field<'a>(message: &Message<'a>, n: usize) -> &'a str {
+    message
+        .segments
+        .first()
+        .and_then(|s| s.field_value(n))
+        .unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    const FIRST: &str =
+        "MSH|^~\\&|LAB|FAC|HIS|FAC|20210101||ADT^A08|CTRL-1|P|2.4|1\rPID|||555-44-4444\rDSC|CTRL-1";
+    const SECOND: &str =
+        "MSH|^~\\&|LAB|FAC|HIS|FAC|20210101||ADT^A08|CTRL-2|P|2.4|2|CTRL-1\rNK1|1|SMITH\rNK1|2|JONES";
+
+    #[test]
+    fn ensure_fragments_are_assembled_in_order() -> Result<(), Hl7ParseError> {
+        let mut assembler = MessageAssembler::new();
+        assembler.add(Message::try_from(SECOND)?);
+        assembler.add(Message::try_from(FIRST)?);
+
+        let hl7 = assembler.assemble()?;
+        let msg = Message::try_from(hl7.as_str())?;
+
+        assert_eq!(msg.segment_names(), vec!["MSH", "PID", "NK1", "NK1"]);
+        assert_eq!(msg.query("MSH.F10"), "CTRL-1");
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_missing_final_continuation_is_an_error() -> Result<(), Hl7ParseError> {
+        let mut assembler = MessageAssembler::new();
+        assembler.add(Message::try_from(FIRST)?);
+
+        assert!(assembler.assemble().is_err());
+
+        // the DSC segment is recognised regardless of case
+        let lower = FIRST.replace("DSC|", "dsc|");
+        let mut assembler = MessageAssembler::new();
+        assembler.add(Message::try_from(lower.as_str())?);
+        assert!(assembler.assemble().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_broken_chain_is_an_error() -> Result<(), Hl7ParseError> {
+        let unrelated =
+            "MSH|^~\\&|LAB|FAC|HIS|FAC|20210101||ADT^A08|CTRL-9|P|2.4|2|CTRL-8\rNK1|1|SMITH";
+        let mut assembler = MessageAssembler::new();
+        assembler.add(Message::try_from(FIRST)?);
+        assembler.add(Message::try_from(unrelated)?);
+
+        assert!(assembler.assemble().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,193 @@
+use crate::Hl7ParseError;
+
+/// The parts of a HL7 `DTM` (date/time) value, eg `20020215093015.1234+1000`, as plain integers.
+/// This avoids taking a dependency on a date/time crate just to read a timestamp, see [`parse_dtm()`].
+///
+/// A DTM may be sent at any precision from the year down to fractions of a second, so everything after the year is optional.
+/// The parts are range checked, but no calendar validation is done (eg the 31st of February is accepted).
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct DtmParts {
+    pub year: u16,
+    pub month: Option<u8>,
+    pub day: Option<u8>,
+    pub hour: Option<u8>,
+    pub minute: Option<u8>,
+    pub second: Option<u8>,
+    /// Fractional seconds in ten-thousandths (the spec allows up to 4 digits), eg `.5` is `5000`
+    pub frac: Option<u16>,
+    /// The offset from UTC in minutes, eg `+1030` is `630` and `-0500` is `-300`
+    pub tz_offset_minutes: Option<i16>,
+}
+
+/// Parses the given HL7 `DTM` value (`YYYY[MM[DD[HH[MM[SS[.S[S[S[S]]]]]]]]][+/-ZZZZ]`) into it's integer parts.
+/// Returns an `Err(Hl7ParseError::Generic)` if the value doesn't match that format, or a part is out of range (eg month 13).
+/// ## Example:
+/// ```
+/// # use rusthl7::Hl7ParseError;
+/// # use rusthl7::parse_dtm;
+/// # fn main() -> Result<(), Hl7ParseError> {
+/// let dtm = parse_dtm("200202150930+1000")?;
+/// assert_eq!(dtm.year, 2002);
+/// assert_eq!(dtm.month, Some(2));
+/// assert_eq!(dtm.minute, Some(30));
+/// assert_eq!(dtm.second, None);
+/// assert_eq!(dtm.tz_offset_minutes, Some(600));
+/// # Ok(())
+/// # }
+/// ```
+pub fn parse_dtm(input: &str) -> Result<DtmParts, Hl7ParseError> {
+    let invalid =
+        |reason: &str| Hl7ParseError::Generic(format!("Invalid DTM '{}': {}", input, reason));
+
+    // the year is never signed, so any sign after it starts the timezone
+    let (value, tz) = match input.get(4..).and_then(|rest| rest.find(['+', '-'])) {
+        Some(idx) => (&input[..idx + 4], Some(&input[idx + 4..])),
+        None => (input, None),
+    };
+    let (digits, frac) = match value.find('.') {
+        Some(idx) => (&value[..idx], Some(&value[idx + 1..])),
+        None => (value, None),
+    };
+
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(invalid(
+            "expected only digits before any fraction or timezone",
+        ));
+    }
+    if !matches!(digits.len(), 4 | 6 | 8 | 10 | 12 | 14) {
+        return Err(invalid(
+            "expected a year, then optionally month, day, hour, minute and second",
+        ));
+    }
+
+    // two digit part starting at the given offset, if it was sent, checked against the allowed range
+    let part = |start: usize, min: u8, max: u8, name: &str| -> Result<Option<u8>, Hl7ParseError> {
+        match digits.get(start..start + 2) {
+            Some(p) => {
+                let value: u8 = p.parse().map_err(|_| invalid(name))?;
+                if value < min || value > max {
+                    return Err(invalid(&format!("{} {} is out of range", name, value)));
+                }
+                Ok(Some(value))
+            }
+            None => Ok(None),
+        }
+    };
+
+    let second = part(12, 0, 59, "second")?;
+
+    let frac = match frac {
+        Some(_) if second.is_none() => return Err(invalid("fractional seconds require seconds")),
+        Some(f) if f.is_empty() || f.len() > 4 || !f.chars().all(|c| c.is_ascii_digit()) => {
+            return Err(invalid("expected 1 to 4 digits of fractional seconds"))
+        }
+        // right pad to ten-thousandths, eg `.5` is 5000
+        Some(f) => Some(
+            format!("{:0<4}", f)
+                .parse()
+                .map_err(|_| invalid("fraction"))?,
+        ),
+        None => None,
+    };
+
+    let tz_offset_minutes = match tz {
+        Some(tz) if tz.len() == 5 && tz[1..].chars().all(|c| c.is_ascii_digit()) => {
+            let hours: i16 = tz[1..3].parse().map_err(|_| invalid("timezone"))?;
+            let minutes: i16 = tz[3..5].parse().map_err(|_| invalid("timezone"))?;
+            if hours > 23 || minutes > 59 {
+                return Err(invalid("timezone offset is out of range"));
+            }
+            let offset = hours * 60 + minutes;
+            Some(if tz.starts_with('-') { -offset } else { offset })
+        }
+        Some(_) => return Err(invalid("expected a timezone of the form +ZZZZ or -ZZZZ")),
+        None => None,
+    };
+
+    Ok(DtmParts {
+        year: digits[..4].parse().map_err(|_| invalid("year"))?,
+        month: part(4, 1, 12, "month")?,
+        day: part(6, 1, 31, "day")?,
+        hour: part(8, 0, 23, "hour")?,
+        minute: part(10, 0, 59, "minute")?,
+        second,
+        frac,
+        tz_offset_minutes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ensure_each_precision_parses() -> Result<(), Hl7ParseError> {
+        let year = parse_dtm("2002")?;
+        assert_eq!(year.year, 2002);
+        assert_eq!(year.month, None);
+
+        let month = parse_dtm("200202")?;
+        assert_eq!(month.month, Some(2));
+        assert_eq!(month.day, None);
+
+        let day = parse_dtm("20020215")?;
+        assert_eq!(day.day, Some(15));
+        assert_eq!(day.hour, None);
+
+        let hour = parse_dtm("2002021509")?;
+        assert_eq!(hour.hour, Some(9));
+        assert_eq!(hour.minute, None);
+
+        let minute = parse_dtm("200202150930")?;
+        assert_eq!(minute.minute, Some(30));
+        assert_eq!(minute.second, None);
+
+        let second = parse_dtm("20020215093015")?;
+        assert_eq!(second.second, Some(15));
+        assert_eq!(second.frac, None);
+        assert_eq!(second.tz_offset_minutes, None);
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_fractions_and_timezones_parse() -> Result<(), Hl7ParseError> {
+        assert_eq!(
+            parse_dtm("20020215093015.1234+1030")?,
+            DtmParts {
+                year: 2002,
+                month: Some(2),
+                day: Some(15),
+                hour: Some(9),
+                minute: Some(30),
+                second: Some(15),
+                frac: Some(1234),
+                tz_offset_minutes: Some(630),
+            }
+        );
+
+        assert_eq!(parse_dtm("20020215093015.5")?.frac, Some(5000));
+        assert_eq!(parse_dtm("20020215093015.05")?.frac, Some(500));
+        assert_eq!(parse_dtm("2002-0500")?.tz_offset_minutes, Some(-300));
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_invalid_values_are_errors() {
+        for bad in &[
+            "",
+            "02",
+            "200213",
+            "20020230250000",
+            "2002021509301",
+            "200202150930.5",
+            "20020215093015.12345",
+            "20020215093015.",
+            "2002+10",
+            "2002+2500",
+            "2002/02/15",
+            "２００２",
+        ] {
+            assert!(parse_dtm(bad).is_err(), "'{}' should not parse", bad);
+        }
+    }
+}
@@ -1,8 +1,11 @@
 use log::{debug, trace};
+use once_cell::sync::Lazy;
 use regex::Regex;
 
 use crate::separators::Separators;
+use crate::Hl7ParseError;
 use std::borrow::Cow;
+use std::collections::HashMap;
 
 /// This struct provides the decoding functionality to parse escape sequences from the source string back to their original chars.
 ///
@@ -49,6 +52,8 @@ impl<'a> EscapeSequence {
     ///
     /// Creating a new [EscapeSequence] does involve some non-trivial work in order to improve the performance of the `decode()` operations.  It's expected that instances of this struct will be cached
     /// per message, or per sending application if it will always use the same separators, or for the lifetime of the process if you're only dealing with known (often default) separators.
+    ///
+    /// This panics if any of the separators aren't ASCII (as required by the spec), which can't happen for [Separators] parsed from a message as those are validated up front.
     pub fn new(delims: Separators) -> EscapeSequence {
         let regex = if delims.escape_char == '\\' {
             Regex::new(r#"\\"#) // needs special handling because backslashes have meaning in regexes, and need to be escaped
@@ -78,8 +83,41 @@ impl<'a> EscapeSequence {
         return_val
     }
 
+    /// Returns a shared instance for the default separators (see [`Separators::DEFAULT`]), built on first use.
+    /// As most messages use the default separators this allows callers to skip the cost of [`EscapeSequence::new()`] entirely.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::EscapeSequence;
+    /// let decoded = EscapeSequence::default().decode(r#"Obstetrician \T\ Gynaecologist"#);
+    /// assert_eq!(decoded, r#"Obstetrician & Gynaecologist"#);
+    /// ```
+    #[allow(clippy::should_implement_trait)] // returns a shared reference, not a new instance
+    pub fn default() -> &'static EscapeSequence {
+        static DEFAULT: Lazy<EscapeSequence> =
+            Lazy::new(|| EscapeSequence::new(Separators::DEFAULT));
+        &DEFAULT
+    }
+
     /// This is where the magic happens.  Call this to update any escape sequences in the given &str.
+    ///
+    /// ⚠ This method will panic if an `\X..\` sequence contains invalid hex, or decodes to invalid UTF-8.  See [`EscapeSequence::decode_checked()`] for a non-panicking alternative. ⚠
     pub fn decode<S>(&self, input: S) -> Cow<'a, str>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.decode_checked(input)
+            .expect("Unable to decode escape sequence")
+    }
+
+    /// Updates any escape sequences in the given &str, as per [`EscapeSequence::decode()`], but returns an `Err(Hl7ParseError::InvalidEscapeSequence)`
+    /// rather than panicking if an `\X..\` sequence contains invalid hex, or decodes to invalid UTF-8.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::{EscapeSequence, Separators};
+    /// let decoder = EscapeSequence::new(Separators::default());
+    /// assert!(decoder.decode_checked(r#"Invalid \XFF\ byte"#).is_err());
+    /// ```
+    pub fn decode_checked<S>(&self, input: S) -> Result<Cow<'a, str>, Hl7ParseError>
     where
         S: Into<Cow<'a, str>>,
     {
@@ -157,18 +195,20 @@ impl<'a> EscapeSequence {
                                 output.extend_from_slice(sequence.as_bytes());
                                 output.extend_from_slice(&self.escape_buf);
                             } else if let Some(hex_code) = sequence.strip_prefix('X') {
-                                let hex = hex::decode(hex_code)
-                                    .expect("Unable to parse X-value into valid hex");
-                                println!("Converted hex code {} to {:?}", hex_code, hex);
+                                let hex = hex::decode(hex_code).map_err(|e| {
+                                    Hl7ParseError::InvalidEscapeSequence(format!(
+                                        "Unable to parse X-value '{}' into valid hex: {}",
+                                        hex_code, e
+                                    ))
+                                })?;
+                                trace!("Converted hex code {} to {:?}", hex_code, hex);
                                 output.extend_from_slice(&hex);
 
                             // TODO: Add more sequences
                             } else {
                                 // not a known sequence, must just be two backslashes randomly in a string
                                 trace!("Unknown sequence, extending output...");
-                                output.extend_from_slice(
-                                    input[start_index - 1..end_index].as_bytes(),
-                                );
+                                output.extend_from_slice(input[start_index..=end_index].as_bytes());
                                 // include both the initial escape char, and also the final one.
                             }
                         }
@@ -177,14 +217,86 @@ impl<'a> EscapeSequence {
                     i = end_index + 1; // move through buffer, we we've covered everything up to this point now
                 } // while more chars in input to loop through
 
-                Cow::Owned(String::from_utf8(output).unwrap())
+                let output = String::from_utf8(output).map_err(|e| {
+                    Hl7ParseError::InvalidEscapeSequence(format!(
+                        "Decoded value is not valid UTF-8: {}",
+                        e
+                    ))
+                })?;
+                Ok(Cow::Owned(output))
             }
             None => {
                 // no escape char in the string at all, just return what we have
-                input
+                Ok(input)
             }
         }
     }
+
+    /// Returns the length (in bytes, including both escape chars) of the escape sequence at the start of `input`, or `None` if it doesn't start with one.
+    /// Only sequences this decoder knows about count, so text that merely sits between two escape chars (eg the `\dir^x\` in a Windows path `C:\dir^x\y`) isn't a sequence.
+    pub(crate) fn sequence_len(&self, input: &str) -> Option<usize> {
+        let escape = char::from(self.escape_buf[0]);
+        let rest = input.strip_prefix(escape)?;
+        let sequence = &rest[..rest.find(escape)?];
+
+        let known = match sequence {
+            "E" | "F" | "R" | "S" | "T" | "H" | "N" => true,
+            _ => {
+                sequence.starts_with('Z')
+                    || sequence.strip_prefix('X').is_some_and(|hex| {
+                        !hex.is_empty()
+                            && hex.len() % 2 == 0
+                            && hex.chars().all(|c| c.is_ascii_hexdigit())
+                    })
+            }
+        };
+
+        if known {
+            Some(sequence.len() + 2 * escape.len_utf8())
+        } else {
+            None
+        }
+    }
+}
+
+/// A cache of [`EscapeSequence`] instances keyed by [`Separators`], for services handling messages from many senders with differing separators.
+/// As creating an [`EscapeSequence`] is relatively expensive this allows a single instance to be shared for each distinct set of separators.
+/// ## Example:
+/// ```
+/// # use rusthl7::{EscaperCache, Separators};
+/// let mut cache = EscaperCache::new();
+/// let decoder = cache.get_or_create(Separators::default());
+/// assert_eq!(decoder.decode(r#"Obstetrician \T\ Gynaecologist"#), "Obstetrician & Gynaecologist");
+/// ```
+#[derive(Default)]
+pub struct EscaperCache {
+    escapers: HashMap<Separators, EscapeSequence>,
+}
+
+impl EscaperCache {
+    /// Create a new, empty cache
+    pub fn new() -> EscaperCache {
+        EscaperCache {
+            escapers: HashMap::new(),
+        }
+    }
+
+    /// Gets the cached [`EscapeSequence`] for the given separators, creating (and caching) it if required.
+    pub fn get_or_create(&mut self, delims: Separators) -> &EscapeSequence {
+        self.escapers
+            .entry(delims)
+            .or_insert_with(|| EscapeSequence::new(delims))
+    }
+
+    /// The number of distinct separator sets currently cached
+    pub fn len(&self) -> usize {
+        self.escapers.len()
+    }
+
+    /// Returns true if nothing has been cached yet
+    pub fn is_empty(&self) -> bool {
+        self.escapers.is_empty()
+    }
 }
 
 #[cfg(test)]
@@ -203,6 +315,60 @@ mod tests {
         assert_eq!(output, input);
     }
 
+    #[test]
+    fn ensure_default_instance_is_shared() {
+        let first = EscapeSequence::default();
+        let second = EscapeSequence::default();
+        assert!(std::ptr::eq(first, second));
+        assert_eq!(
+            first.decode(r#"Escape this \F\ please"#),
+            "Escape this | please"
+        );
+    }
+
+    #[test]
+    fn ensure_decode_checked_errors_on_invalid_utf8() {
+        let escaper = EscapeSequence::new(Separators::default());
+
+        let result = escaper.decode_checked(r#"Invalid \XFF\ byte"#);
+        assert!(matches!(
+            result,
+            Err(Hl7ParseError::InvalidEscapeSequence(_))
+        ));
+
+        let result = escaper.decode_checked(r#"Invalid \XZZ\ hex"#);
+        assert!(result.is_err());
+
+        let result = escaper.decode_checked(r#"Valid \X41\ hex"#);
+        assert_eq!(result.unwrap(), "Valid A hex");
+    }
+
+    #[test]
+    fn ensure_escaper_cache_is_keyed_by_separators() {
+        use crate::Message;
+        use std::convert::TryFrom;
+
+        let first = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3\rOBR|1|a \\T\\ b").unwrap();
+        let second = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3\rOBR|2").unwrap();
+        let third = Message::try_from("MSH^!@#$^GHH LAB^ELAB-3\rOBR^1^a #T# b").unwrap();
+
+        let mut cache = EscaperCache::new();
+        assert!(cache.is_empty());
+
+        let decoded = cache
+            .get_or_create(first.get_separators())
+            .decode(first.query("OBR.F2"));
+        assert_eq!(decoded, "a & b");
+        cache.get_or_create(second.get_separators());
+        assert_eq!(cache.len(), 1); // hit
+
+        let decoded = cache
+            .get_or_create(third.get_separators())
+            .decode(third.query("OBR.F2"));
+        assert_eq!(decoded, "a $ b");
+        assert_eq!(cache.len(), 2); // miss
+    }
+
     #[test]
     fn test_decode_handles_simple_x_codes() {
         let delims = Separators::default();
@@ -233,6 +399,31 @@ mod tests {
         assert_eq!(output, input);
     }
 
+    #[test]
+    fn ensure_decode_handles_escape_char_at_end_of_input() {
+        let escaper = EscapeSequence::new(Separators::default());
+
+        let input = r#"value\"#;
+        assert_eq!(escaper.decode(input), input);
+
+        let input = r#"\"#;
+        assert_eq!(escaper.decode(input), input);
+
+        let input = r#"\F\\"#;
+        assert_eq!(escaper.decode(input), r#"|\"#);
+    }
+
+    #[test]
+    fn ensure_decode_preserves_unknown_sequences_verbatim() {
+        let escaper = EscapeSequence::new(Separators::default());
+
+        let input = r#"\ab\ at the start"#;
+        assert_eq!(escaper.decode(input), input);
+
+        let input = r#"x\ab\y"#;
+        assert_eq!(escaper.decode(input), input);
+    }
+
     #[test]
     fn test_decode_handles_field_sequence() {
         let delims = Separators::default();
@@ -318,6 +509,14 @@ mod tests {
         assert_eq!(output, input);
     }
 
+    #[test]
+    fn ensure_decode_preserves_short_custom_sequence() {
+        let escaper = EscapeSequence::new(Separators::default());
+
+        let input = r#"\Z99\"#;
+        assert_eq!(escaper.decode(input), input);
+    }
+
     #[test]
     fn ensure_decode_ignores_custom_sequence() {
         let delims = Separators::default();
@@ -1,5 +1,6 @@
 use super::separators::Separators;
 use super::*;
+use std::borrow::Cow;
 use std::fmt::Display;
 use std::ops::Index;
 
@@ -8,7 +9,7 @@ use std::ops::Index;
 #[derive(Debug, PartialEq)]
 pub struct Field<'a> {
     pub source: &'a str,
-    delims: Separators,
+    pub(crate) delims: Separators,
     pub repeats: Vec<&'a str>,
     pub components: Vec<Vec<&'a str>>,
     pub subcomponents: Vec<Vec<Vec<&'a str>>>,
@@ -16,6 +17,8 @@ pub struct Field<'a> {
 
 impl<'a> Field<'a> {
     /// Convert the given line of text into a field.
+    /// Note that this splits on _every_ separator char found, so any data that legitimately contains a separator char must
+    /// already be escaped (eg `^` as `\S\`) by the sender.  See [`Field::parse_escaped()`] if the data may contain escape sequences wrapping separator chars.
     pub fn parse<S: Into<&'a str>>(
         input: S,
         delims: &Separators,
@@ -44,6 +47,83 @@ impl<'a> Field<'a> {
         Ok(field)
     }
 
+    /// Convert the given line of text into a field, splitting only on separator chars that are _not_ inside an escape sequence.
+    /// This means sequences such as `\S\` or custom sequences such as `\Zabc^def\` are kept as data in a single component rather than being split.
+    /// Only the sequences known to `escaper` are skipped over, an escape char that doesn't start one (eg the backslashes in `C:\dir^x\y`) is treated as plain data.
+    /// The `escaper` should be built from the same `delims`.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::{EscapeSequence, Field, Separators};
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let f = Field::parse_escaped(r#"\Zabc^def\^ghi"#, &Separators::default(), EscapeSequence::default())?;
+    /// assert_eq!(f.components[0], vec![r#"\Zabc^def\"#, "ghi"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn parse_escaped<S: Into<&'a str>>(
+        input: S,
+        delims: &Separators,
+        escaper: &EscapeSequence,
+    ) -> Result<Field<'a>, Hl7ParseError> {
+        let input = input.into();
+        let repeats: Vec<&'a str> = split_unescaped(input, delims.repeat, escaper);
+        let components: Vec<Vec<&'a str>> = repeats
+            .iter()
+            .map(|r| split_unescaped(r, delims.component, escaper))
+            .collect();
+        let subcomponents: Vec<Vec<Vec<&'a str>>> = components
+            .iter()
+            .map(|r| {
+                r.iter()
+                    .map(|c| split_unescaped(c, delims.subcomponent, escaper))
+                    .collect::<Vec<Vec<&'a str>>>()
+            })
+            .collect();
+        let field = Field {
+            source: input,
+            delims: *delims,
+            repeats,
+            components,
+            subcomponents,
+        };
+        Ok(field)
+    }
+
+    /// Convert the given line of text into a field as per [`Field::parse()`], with control over whitespace around the values.
+    /// Some feeds pad values to a fixed width, `ComponentWhitespace::Trim` strips that padding from each repeat, component and subcomponent as it's parsed.
+    /// The field's `source` is always left untouched.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::{ComponentWhitespace, Field, Separators};
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let f = Field::parse_with("a ^ b ", &Separators::default(), ComponentWhitespace::Trim)?;
+    /// assert_eq!(f.components[0], vec!["a", "b"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn parse_with<S: Into<&'a str>>(
+        input: S,
+        delims: &Separators,
+        whitespace: ComponentWhitespace,
+    ) -> Result<Field<'a>, Hl7ParseError> {
+        let mut field = Field::parse(input, delims)?;
+
+        if whitespace == ComponentWhitespace::Trim {
+            for repeat in field.repeats.iter_mut() {
+                *repeat = repeat.trim();
+            }
+            for component in field.components.iter_mut().flatten() {
+                *component = component.trim();
+            }
+            for subcomponent in field.subcomponents.iter_mut().flatten().flatten() {
+                *subcomponent = subcomponent.trim();
+            }
+        }
+        Ok(field)
+    }
+
     /// Used to hide the removal of NoneError for #2...  
     /// If passed `Some()` value it returns a field with that value.  
     /// If passed `None` it returns an `Err(Hl7ParseError::MissingRequiredValue{})`
@@ -75,7 +155,7 @@ impl<'a> Field<'a> {
     }
 
     /// Compatibility method to get the underlying value of this field.
-    /// NOTE that this is deprecated as a duplicate of  [`Field::as_str()`].
+    /// NOTE that this is deprecated as a duplicate of  [`Field::as_str()`], both return exactly the same value.
     ///
     /// This function was chosen as the deprecation victim as a future version of the library may include strongly typed Field's (eg DateTime)
     /// at which point a generically typed 'value()' function will need to be implemented.
@@ -94,43 +174,286 @@ impl<'a> Field<'a> {
         self.source
     }
 
-    /// Access string reference of a Field component by String index
+    /// Gets the given (zero based) repeat of this field, or `None` if there aren't that many repeats.
+    /// Unlike indexing (`field[n]`) this distinguishes a missing repeat from an empty one, which is handy when iterating phone numbers, addresses etc.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::{Field, Separators};
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let f = Field::parse("a~b~c", &Separators::default())?;
+    /// assert_eq!(f.repeat(1), Some("b"));
+    /// assert_eq!(f.repeat(3), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn repeat(&self, index: usize) -> Option<&'a str> {
+        self.repeats.get(index).copied()
+    }
+
+    /// Iterates the repeats of this field with any escape sequences decoded by the given escaper (see [`EscapeSequence::decode()`]).
+    /// Repeats without escape sequences are borrowed rather than allocated.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::{EscapeSequence, Field, Separators};
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let delims = Separators::default();
+    /// let f = Field::parse(r#"a\T\b~c"#, &delims)?;
+    /// let decoded: Vec<_> = f.iter_repeats_decoded(&EscapeSequence::new(delims)).collect();
+    /// assert_eq!(decoded, vec!["a&b", "c"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter_repeats_decoded<'b>(
+        &'b self,
+        escaper: &'b EscapeSequence,
+    ) -> impl Iterator<Item = Cow<'a, str>> + 'b {
+        self.repeats
+            .iter()
+            .map(move |repeat| escaper.decode(*repeat))
+    }
+
+    /// Gets the given (zero based) component of the first repeat, interpreting the HL7 null value (`""`, two double quotes) as `None`.
+    /// A genuinely empty component returns `Some("")`, as does a component that wasn't sent at all.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::{Field, Separators};
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let f = Field::parse(r#"a^""^^c"#, &Separators::default())?;
+    /// assert_eq!(f.component_value(0), Some("a"));
+    /// assert_eq!(f.component_value(1), None); // HL7 null
+    /// assert_eq!(f.component_value(2), Some("")); // empty
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn component_value(&self, index: usize) -> Option<&'a str> {
+        match self[(0, index)] {
+            "\"\"" => None,
+            value => Some(value),
+        }
+    }
+
+    /// Interprets the first repeat of this field as a structured numeric (`SN`) value, eg an OBX-5 with a value type of `SN`.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::{Field, Separators};
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let f = Field::parse(">^5^-^10", &Separators::default())?;
+    /// let sn = f.as_structured_numeric();
+    /// assert_eq!(sn.comparator, ">");
+    /// assert_eq!(sn.num1, Some(5.0));
+    /// assert_eq!(sn.separator, "-");
+    /// assert_eq!(sn.num2, Some(10.0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn as_structured_numeric(&self) -> StructuredNumeric<'a> {
+        StructuredNumeric::from_components(&self.components[0])
+    }
+
+    /// Gets the first component of the first repeat of this field (typically the "primary value"), or `""` for an empty field.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::{Field, Separators};
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let f = Field::parse("a^b^c", &Separators::default())?;
+    /// assert_eq!(f.first_component(), "a");
+    /// assert_eq!(f.last_component(), "c");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn first_component(&self) -> &'a str {
+        self[(0, 0)]
+    }
+
+    /// Gets the last component of the first repeat of this field (often a qualifier), or `""` for an empty field.
+    pub fn last_component(&self) -> &'a str {
+        self.components[0].last().copied().unwrap_or("")
+    }
+
+    /// Joins the non-empty components of the first repeat of this field using the given separator, rather than the HL7 component separator.
+    /// This is commonly used for rendering (eg a name field `SMITH^JOHN^^^A` as `SMITH JOHN A`).  If you need _all_ components, including empty ones,
+    /// use `field.components[0].join(sep)` instead.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::{Field, Separators};
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let f = Field::parse("SMITH^JOHN^^^A", &Separators::default())?;
+    /// assert_eq!(f.components_joined(" "), "SMITH JOHN A");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn components_joined(&self, sep: &str) -> String {
+        self.components[0]
+            .iter()
+            .filter(|c| !c.is_empty())
+            .copied()
+            .collect::<Vec<&str>>()
+            .join(sep)
+    }
+
+    /// Gets the subcomponents of the given (zero based) component of the first repeat, with any trailing empty subcomponents removed.
+    /// The raw `subcomponents` are left as-is, so a trailing separator (eg `a&b&`) can still be detected there if needed.
+    /// Returns an empty `Vec` if the component doesn't exist.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::{Field, Separators};
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let f = Field::parse("a&b&", &Separators::default())?;
+    /// assert_eq!(f.subcomponents_trimmed(0), vec!["a", "b"]);
+    /// assert_eq!(f.subcomponents[0][0], vec!["a", "b", ""]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn subcomponents_trimmed(&self, component: usize) -> Vec<&'a str> {
+        let mut subcomponents = match self.subcomponents[0].get(component) {
+            Some(s) => s.clone(),
+            None => return Vec::new(),
+        };
+
+        while subcomponents.last() == Some(&"") {
+            subcomponents.pop();
+        }
+        subcomponents
+    }
+
+    /// Access string reference of a Field repeat, component or subcomponent by String index (eg `R1.C2.S1`)
     /// Adjust the index by one as medical people do not count from zero
+    ///
+    /// Whitespace within a part is ignored (eg `R 2`), and a bare prefix without a number (eg `R`) means the first, so `R.C2` is equivalent to `R1.C2`.
+    /// Parts without a number that aren't a bare prefix (eg `XY`) return an empty string.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::{Field, Separators};
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let f = Field::parse("a^b~c", &Separators::default())?;
+    /// assert_eq!(f.query("R.C2"), "b");
+    /// assert_eq!(f.query(" R 2 "), "c");
+    /// # Ok(())
+    /// # }
+    /// ```
     pub fn query<'b, S>(&self, sidx: S) -> &'a str
     where
         S: Into<&'b str>,
     {
         let sidx = sidx.into();
         let parts = sidx.split('.').collect::<Vec<&str>>();
+        self.query_parts(&parts)
+    }
+
+    /// As per [`Field::query()`], but taking the path already split into it's parts (eg `["R1", "C2"]`).
+    /// This is used when drilling down from [`crate::Segment::query_parts()`] to avoid re-joining the remaining path.
+    pub fn query_parts(&self, parts: &[&str]) -> &'a str {
+        // parts without digits (eg `RC`) or a zero index can't be resolved
+        let index = |part: &str| Self::try_parse_index(part);
+        let value = match parts {
+            [repeat] => index(repeat).map(|r| self[r]),
+            [repeat, component] => index(repeat)
+                .zip(index(component))
+                .map(|(r, c)| self[(r, c)]),
+            [repeat, component, subcomponent] => index(repeat)
+                .zip(index(component))
+                .zip(index(subcomponent))
+                .map(|((r, c), s)| self[(r, c, s)]),
+            _ => None,
+        };
+        value.unwrap_or("")
+    }
+
+    /// As per [`Field::query()`], but distinguishes a value that is present but empty (`Some("")`) from one that doesn't exist at all (`None`).
+    /// The path may also include a subcomponent (eg `R1.C2.S1`).
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::{Field, Separators};
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let f = Field::parse("a^^c", &Separators::default())?;
+    /// assert_eq!(f.try_query("R1.C2"), Some("")); // present, but empty
+    /// assert_eq!(f.try_query("R1.C9"), None); // absent
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn try_query<'b, S>(&self, sidx: S) -> Option<&'a str>
+    where
+        S: Into<&'b str>,
+    {
+        let sidx = sidx.into();
+        let parts = sidx.split('.').collect::<Vec<&str>>();
+        self.try_query_parts(&parts)
+    }
 
-        if parts.len() == 1 {
-            let stringnums = parts[0]
-                .chars()
-                .filter(|c| c.is_ascii_digit())
-                .collect::<String>();
-            let idx: usize = stringnums.parse().unwrap();
+    /// As per [`Field::try_query()`], but taking the path already split into it's parts (eg `["R1", "C2"]`).
+    pub fn try_query_parts(&self, parts: &[&str]) -> Option<&'a str> {
+        self.query_ref(parts).copied()
+    }
 
-            self[idx - 1]
-        } else if parts.len() == 2 {
-            let stringnums = parts[0]
-                .chars()
-                .filter(|c| c.is_ascii_digit())
-                .collect::<String>();
+    /// As per [`Field::try_query_parts()`], but returning a reference to the stored value, as needed by the string `Index` impls.
+    pub(crate) fn query_ref(&self, parts: &[&str]) -> Option<&&'a str> {
+        match parts {
+            [repeat] => self.repeats.get(Self::try_parse_index(repeat)?),
+            [repeat, component] => self
+                .components
+                .get(Self::try_parse_index(repeat)?)?
+                .get(Self::try_parse_index(component)?),
+            [repeat, component, subcomponent] => self
+                .subcomponents
+                .get(Self::try_parse_index(repeat)?)?
+                .get(Self::try_parse_index(component)?)?
+                .get(Self::try_parse_index(subcomponent)?),
+            _ => None,
+        }
+    }
 
-            let idx0: usize = stringnums.parse().unwrap();
+    /// Extracts the (one based) numeric value from a path part such as `C2` and converts it to a zero based index, or `None` if that isn't possible.
+    /// A bare prefix without a number (eg `R`) means the first.
+    fn try_parse_index(part: &str) -> Option<usize> {
+        let stringnums = part
+            .chars()
+            .filter(|c| c.is_ascii_digit())
+            .collect::<String>();
+        if stringnums.is_empty() && matches!(part.trim(), "R" | "r" | "C" | "c" | "S" | "s") {
+            return Some(0);
+        }
+        stringnums.parse::<usize>().ok()?.checked_sub(1)
+    }
+}
 
-            let stringnums = parts[1]
-                .chars()
-                .filter(|c| c.is_ascii_digit())
-                .collect::<String>();
+/// Controls how [`Field::parse_with()`] treats whitespace around repeats, components and subcomponents.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum ComponentWhitespace {
+    /// Keep the values exactly as sent (the default, and the behaviour of [`Field::parse()`])
+    #[default]
+    Preserve,
+    /// Trim leading and trailing whitespace from each value, for feeds known to pad values to a fixed width
+    Trim,
+}
 
-            let idx1: usize = stringnums.parse().unwrap();
+/// Splits the input on `separator`, ignoring any separators that fall inside an escape sequence known to `escaper`.
+fn split_unescaped<'a>(input: &'a str, separator: char, escaper: &EscapeSequence) -> Vec<&'a str> {
+    let mut result = Vec::new();
+    let mut start = 0;
+    let mut skip_to = 0;
 
-            self[(idx0 - 1, idx1 - 1)]
-        } else {
-            ""
+    for (i, c) in input.char_indices() {
+        if i < skip_to {
+            continue; // still inside an escape sequence
+        }
+        if c == separator {
+            result.push(&input[start..i]);
+            start = i + c.len_utf8();
+        } else if let Some(len) = escaper.sequence_len(&input[i..]) {
+            skip_to = i + len;
         }
     }
+    result.push(&input[start..]);
+    result
 }
 
 impl<'a> Display for Field<'a> {
@@ -140,10 +463,24 @@ impl<'a> Display for Field<'a> {
     }
 }
 
+impl<'a> AsRef<str> for Field<'a> {
+    /// Returns the source string for this field, allowing it to be passed directly to APIs taking `impl AsRef<str>`
+    fn as_ref(&self) -> &str {
+        self.source
+    }
+}
+
 impl<'a> Clone for Field<'a> {
-    /// Creates a new Message object using a clone of the original's source
+    /// Creates a new Field referencing the same source slice as the original.
+    /// The already split values are cloned rather than re-parsing the source, so fields from [`Field::parse_with()`] or [`Field::parse_escaped()`] keep their values.
     fn clone(&self) -> Self {
-        Field::parse(self.source, &self.delims.clone()).unwrap()
+        Field {
+            source: self.source,
+            delims: self.delims,
+            repeats: self.repeats.clone(),
+            components: self.components.clone(),
+            subcomponents: self.subcomponents.clone(),
+        }
     }
 }
 
@@ -151,7 +488,7 @@ impl<'a> Index<usize> for Field<'a> {
     type Output = &'a str;
     /// Access string reference of a Field component by numeric index
     fn index(&self, idx: usize) -> &Self::Output {
-        if idx > self.repeats.len() - 1 {
+        if idx >= self.repeats.len() {
             return &""; //TODO: We're returning &&str here which doesn't seem right?!?
         }
 
@@ -163,7 +500,7 @@ impl<'a> Index<(usize, usize)> for Field<'a> {
     type Output = &'a str;
     /// Access string reference of a Field subcomponent by numeric index
     fn index(&self, idx: (usize, usize)) -> &Self::Output {
-        if idx.0 > self.repeats.len() - 1 || idx.1 > self.components[idx.0].len() - 1 {
+        if idx.0 >= self.repeats.len() || idx.1 >= self.components[idx.0].len() {
             return &""; //TODO: We're returning &&str here which doesn't seem right?!?
         }
 
@@ -175,9 +512,9 @@ impl<'a> Index<(usize, usize, usize)> for Field<'a> {
     type Output = &'a str;
     /// Access string reference of a Field subcomponent by numeric index
     fn index(&self, idx: (usize, usize, usize)) -> &Self::Output {
-        if idx.0 > self.repeats.len() - 1
-            || idx.1 > self.components[idx.0].len() - 1
-            || idx.2 > self.subcomponents[idx.0][idx.1].len() - 1
+        if idx.0 >= self.repeats.len()
+            || idx.1 >= self.components[idx.0].len()
+            || idx.2 >= self.subcomponents[idx.0][idx.1].len()
         {
             return &""; //TODO: We're returning &&str here which doesn't seem right?!?
         }
@@ -190,63 +527,11 @@ impl<'a> Index<(usize, usize, usize)> for Field<'a> {
 impl<'a> Index<String> for Field<'a> {
     type Output = &'a str;
 
-    /// Access string reference of a Field component by String index
+    /// Access string reference of a Field component by String index, resolved as per [`Field::query()`]
     #[cfg(feature = "string_index")]
     fn index(&self, sidx: String) -> &Self::Output {
         let parts = sidx.split('.').collect::<Vec<&str>>();
-        match parts.len() {
-            1 => {
-                let stringnums = parts[0]
-                    .chars()
-                    .filter(|c| c.is_digit(10))
-                    .collect::<String>();
-                let idx: usize = stringnums.parse().unwrap();
-
-                &self[idx - 1]
-            }
-            2 => {
-                let stringnums = parts[0]
-                    .chars()
-                    .filter(|c| c.is_digit(10))
-                    .collect::<String>();
-
-                let idx0: usize = stringnums.parse().unwrap();
-
-                let stringnums = parts[1]
-                    .chars()
-                    .filter(|c| c.is_digit(10))
-                    .collect::<String>();
-
-                let idx1: usize = stringnums.parse().unwrap();
-
-                &self[(idx0 - 1, idx1 - 1)]
-            }
-            3 => {
-                let stringnums = parts[0]
-                    .chars()
-                    .filter(|c| c.is_digit(10))
-                    .collect::<String>();
-
-                let idx0: usize = stringnums.parse().unwrap();
-
-                let stringnums = parts[1]
-                    .chars()
-                    .filter(|c| c.is_digit(10))
-                    .collect::<String>();
-
-                let idx1: usize = stringnums.parse().unwrap();
-
-                let stringnums = parts[2]
-                    .chars()
-                    .filter(|c| c.is_digit(10))
-                    .collect::<String>();
-
-                let idx2: usize = stringnums.parse().unwrap();
-
-                &self[(idx0 - 1, idx1 - 1, idx2 - 1)]
-            }
-            _ => &"",
-        }
+        self.query_ref(&parts).unwrap_or(&"")
     }
 }
 
@@ -266,6 +551,7 @@ mod tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_conditional_parse_handles_none() {
         let d = Separators::default();
 
@@ -277,6 +563,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_conditional_parse_handles_empty_string() {
         let d = Separators::default();
 
@@ -288,6 +575,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_conditional_parse_handles_value_string() {
         let d = Separators::default();
 
@@ -299,6 +587,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_parse_mandatory_handles_some_value() {
         let d = Separators::default();
 
@@ -309,6 +598,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_parse_mandatory_throws_on_none() {
         let d = Separators::default();
 
@@ -324,6 +614,22 @@ mod tests {
         assert_eq!(f.repeats.len(), 2)
     }
 
+    #[test]
+    fn test_parse_repeat_edge_cases() {
+        let d = Separators::default();
+
+        let f = Field::parse_mandatory(Some("single"), &d).unwrap();
+        assert_eq!(f.repeats, vec!["single"]);
+
+        let f = Field::parse_mandatory(Some("a~~c"), &d).unwrap();
+        assert_eq!(f.repeats, vec!["a", "", "c"]);
+
+        let f = Field::parse_mandatory(Some("a^b~c^d"), &d).unwrap();
+        assert_eq!(f.components, vec![vec!["a", "b"], vec!["c", "d"]]);
+        assert_eq!(f[1], "c^d");
+        assert_eq!(f[(1, 1)], "d");
+    }
+
     #[test]
     fn test_parse_components() {
         let d = Separators::default();
@@ -338,6 +644,170 @@ mod tests {
         assert_eq!(f.subcomponents[0][1].len(), 2)
     }
 
+    #[test]
+    fn test_parse_escaped_ignores_escaped_separators() {
+        let d = Separators::default();
+        let escaper = EscapeSequence::new(d);
+        let f = Field::parse_escaped(r#"a\S\b^c"#, &d, &escaper).unwrap();
+        assert_eq!(f.components[0], vec![r#"a\S\b"#, "c"]);
+
+        // the separators inside the custom sequence would be split by `parse()`
+        let source = r#"\Zx^y&z~w\^c~d"#;
+        let f = Field::parse_escaped(source, &d, &escaper).unwrap();
+        assert_eq!(f.repeats, vec![r#"\Zx^y&z~w\^c"#, "d"]);
+        assert_eq!(f.components[0], vec![r#"\Zx^y&z~w\"#, "c"]);
+        assert_eq!(f.subcomponents[0][0], vec![r#"\Zx^y&z~w\"#]);
+        assert_eq!(Field::parse(source, &d).unwrap().repeats.len(), 3);
+    }
+
+    #[test]
+    fn test_parse_escaped_treats_unknown_escapes_as_data() {
+        let d = Separators::default();
+        let escaper = EscapeSequence::new(d);
+
+        let f = Field::parse_escaped(r#"C:\temp^c"#, &d, &escaper).unwrap();
+        assert_eq!(f.components[0], vec![r#"C:\temp"#, "c"]);
+
+        // a pair of escape chars that isn't a real sequence doesn't hide the separator between them
+        let f = Field::parse_escaped(r#"C:\dir^x\y"#, &d, &escaper).unwrap();
+        assert_eq!(f.components[0], vec![r#"C:\dir"#, r#"x\y"#]);
+
+        // ... but a real sequence after a lone escape char is still honoured
+        let f = Field::parse_escaped(r#"C:\dir\T\x&y"#, &d, &escaper).unwrap();
+        assert_eq!(f.subcomponents[0][0], vec![r#"C:\dir\T\x"#, "y"]);
+    }
+
+    #[test]
+    fn test_first_and_last_component() {
+        let d = Separators::default();
+        let f = Field::parse_mandatory(Some("a^b^c"), &d).unwrap();
+        assert_eq!(f.first_component(), "a");
+        assert_eq!(f.last_component(), "c");
+
+        let f = Field::parse_mandatory(Some("a"), &d).unwrap();
+        assert_eq!(f.first_component(), "a");
+        assert_eq!(f.last_component(), "a");
+
+        let f = Field::parse_mandatory(Some(""), &d).unwrap();
+        assert_eq!(f.first_component(), "");
+        assert_eq!(f.last_component(), "");
+    }
+
+    #[test]
+    fn test_components_joined() {
+        let d = Separators::default();
+        let f = Field::parse_mandatory(Some("EVERYWOMAN^EVE^E^^^^L"), &d).unwrap();
+        assert_eq!(f.components_joined(" "), "EVERYWOMAN EVE E L");
+        assert_eq!(f.components_joined(", "), "EVERYWOMAN, EVE, E, L");
+
+        let f = Field::parse_mandatory(Some(""), &d).unwrap();
+        assert_eq!(f.components_joined(" "), "");
+    }
+
+    #[test]
+    fn test_structured_numeric_value() {
+        let d = Separators::default();
+        let f = Field::parse_mandatory(Some("^182"), &d).unwrap();
+        let sn = f.as_structured_numeric();
+        assert_eq!(sn.comparator, "");
+        assert_eq!(sn.num1, Some(182.0));
+        assert_eq!(sn.separator, "");
+        assert_eq!(sn.num2, None);
+        assert!(!sn.is_range());
+    }
+
+    #[test]
+    fn test_structured_numeric_range() {
+        let d = Separators::default();
+        let f = Field::parse_mandatory(Some(">^5^-^10"), &d).unwrap();
+        let sn = f.as_structured_numeric();
+        assert_eq!(sn.comparator, ">");
+        assert_eq!(sn.num1, Some(5.0));
+        assert_eq!(sn.separator, "-");
+        assert_eq!(sn.num2, Some(10.0));
+        assert!(sn.is_range());
+    }
+
+    #[test]
+    fn test_parse_with_whitespace_options() {
+        let d = Separators::default();
+        let padded = " a ^ b & c ~ d ";
+
+        let preserved = Field::parse_with(padded, &d, ComponentWhitespace::default()).unwrap();
+        assert_eq!(preserved, Field::parse(padded, &d).unwrap());
+        assert_eq!(preserved.components[0], vec![" a ", " b & c "]);
+
+        let trimmed = Field::parse_with(padded, &d, ComponentWhitespace::Trim).unwrap();
+        assert_eq!(trimmed.source, padded);
+        assert_eq!(trimmed.repeats, vec!["a ^ b & c", "d"]);
+        assert_eq!(trimmed.components[0], vec!["a", "b & c"]);
+        assert_eq!(trimmed.subcomponents[0][1], vec!["b", "c"]);
+        assert_eq!(trimmed[(1, 0)], "d");
+    }
+
+    #[test]
+    fn test_clone_keeps_parsed_values() {
+        let d = Separators::default();
+
+        let trimmed = Field::parse_with(" a ^ b ", &d, ComponentWhitespace::Trim).unwrap();
+        let cloned = trimmed.clone();
+        assert_eq!(cloned.components[0], vec!["a", "b"]);
+        assert_eq!(cloned, trimmed);
+
+        let escaped =
+            Field::parse_escaped(r#"\Zx^y&z~w\^c~d"#, &d, EscapeSequence::default()).unwrap();
+        let cloned = escaped.clone();
+        assert_eq!(cloned.components[0], vec![r#"\Zx^y&z~w\"#, "c"]);
+        assert_eq!(cloned, escaped);
+    }
+
+    #[test]
+    fn test_iter_repeats_decoded() {
+        let d = Separators::default();
+        let escaper = EscapeSequence::new(d);
+        let f = Field::parse_mandatory(Some(r#"a\T\b~c"#), &d).unwrap();
+
+        let decoded: Vec<Cow<str>> = f.iter_repeats_decoded(&escaper).collect();
+        assert_eq!(decoded, vec!["a&b", "c"]);
+        assert!(matches!(decoded[0], Cow::Owned(_)));
+        assert!(matches!(decoded[1], Cow::Borrowed("c")));
+    }
+
+    #[test]
+    fn test_query_without_digits_is_empty() {
+        let d = Separators::default();
+        let f = Field::parse_mandatory(Some("a~b^c&d"), &d).unwrap();
+
+        assert_eq!(f.query("XY"), "");
+        assert_eq!(f.query("R2.CX"), "");
+        assert_eq!(f.query("R0.C1"), "");
+        assert_eq!(f.query("R2.C2.S2"), "d");
+    }
+
+    #[test]
+    fn test_query_bare_prefix_means_first() {
+        let d = Separators::default();
+        let f = Field::parse_mandatory(Some("a^b~c^d&e"), &d).unwrap();
+
+        assert_eq!(f.query("R"), "a^b");
+        assert_eq!(f.query("R "), "a^b");
+        assert_eq!(f.query("R2"), "c^d&e");
+        assert_eq!(f.query(" R 2 "), "c^d&e");
+        assert_eq!(f.query("R2.C"), "c");
+        assert_eq!(f.query("R2.C2.S"), "d");
+        assert_eq!(f.try_query("r.c2"), Some("b"));
+        assert_eq!(f.query("X"), "");
+        assert_eq!(f.query(" "), "");
+    }
+
+    #[test]
+    #[allow(deprecated)] // `value()` must keep working until it's removed
+    fn test_value_and_as_str_agree() {
+        let d = Separators::default();
+        let f = Field::parse_mandatory(Some("xxx^yyy&zzz"), &d).unwrap();
+        assert_eq!(f.value(), f.as_str());
+    }
+
     #[test]
     fn test_to_string() {
         let d = Separators::default();
@@ -360,6 +830,50 @@ mod tests {
         assert_eq!(f[(0, 1, 1)], "zzz");
     }
 
+    #[test]
+    fn test_repeat_access() {
+        let d = Separators::default();
+        let f = Field::parse_mandatory(Some("a~b~c"), &d).unwrap();
+        assert_eq!(f.repeat(0), Some("a"));
+        assert_eq!(f.repeat(1), Some("b"));
+        assert_eq!(f.repeat(2), Some("c"));
+        assert_eq!(f.repeat(3), None);
+
+        let f = Field::parse_mandatory(Some("a~~c"), &d).unwrap();
+        assert_eq!(f.repeat(1), Some(""));
+    }
+
+    #[test]
+    fn test_component_value_collapses_null() {
+        let d = Separators::default();
+        let f = Field::parse_mandatory(Some(r#"a^""^c"#), &d).unwrap();
+        assert_eq!(f.component_value(0), Some("a"));
+        assert_eq!(f.component_value(1), None);
+        assert_eq!(f.component_value(2), Some("c"));
+        assert_eq!(f.component_value(3), Some(""));
+        // the raw value is still available
+        assert_eq!(f[(0, 1)], r#""""#);
+    }
+
+    #[test]
+    fn test_tuple_index_boundaries() {
+        let d = Separators::default();
+        let f = Field::parse_mandatory(Some("a^b&c~d"), &d).unwrap();
+
+        assert_eq!(f[1], "d");
+        assert_eq!(f[2], "");
+
+        assert_eq!(f[(0, 1)], "b&c");
+        assert_eq!(f[(0, 2)], "");
+        assert_eq!(f[(1, 0)], "d");
+        assert_eq!(f[(2, 0)], "");
+
+        assert_eq!(f[(0, 1, 1)], "c");
+        assert_eq!(f[(0, 1, 2)], "");
+        assert_eq!(f[(0, 2, 0)], "");
+        assert_eq!(f[(2, 0, 0)], "");
+    }
+
     #[test]
     fn test_string_query() {
         let d = Separators::default();
@@ -369,6 +883,50 @@ mod tests {
         assert_eq!(f.query(&*idx0), "a&a^b&b");
         assert_eq!(f.query("R2.C2"), "b&b");
         assert_eq!(f.query(oob), "");
+        assert_eq!(f.query("R2.C2.S1"), "b");
+        assert_eq!(f.query("R1.C1.S2"), "x");
+        assert_eq!(f.query("R1.C1.S3"), "");
+    }
+
+    #[test]
+    fn test_try_query_distinguishes_empty_from_absent() {
+        let d = Separators::default();
+        let f = Field::parse_mandatory(Some("a^^c&d~e"), &d).unwrap();
+        assert_eq!(f.try_query("R1.C2"), Some(""));
+        assert_eq!(f.try_query("R1.C9"), None);
+        assert_eq!(f.try_query("R1.C3"), Some("c&d"));
+        assert_eq!(f.try_query("R1.C3.S2"), Some("d"));
+        assert_eq!(f.try_query("R1.C3.S3"), None);
+        assert_eq!(f.try_query("R2"), Some("e"));
+        assert_eq!(f.try_query("R3"), None);
+        assert_eq!(f.try_query("R0"), None);
+        // the existing query can't tell the difference
+        assert_eq!(f.query("R1.C2"), f.query("R1.C9"));
+    }
+
+    #[test]
+    fn test_as_ref_str() {
+        fn length_of<S: AsRef<str>>(value: S) -> usize {
+            value.as_ref().len()
+        }
+
+        let d = Separators::default();
+        let f = Field::parse_mandatory(Some("xxx^yyy&zzz"), &d).unwrap();
+        assert_eq!(f.as_ref(), "xxx^yyy&zzz");
+        assert_eq!(length_of(&f), 11);
+    }
+
+    #[test]
+    fn test_subcomponents_trimmed() {
+        let d = Separators::default();
+        let f = Field::parse_mandatory(Some("x^a&b&"), &d).unwrap();
+        assert_eq!(f.subcomponents[0][1], vec!["a", "b", ""]);
+        assert_eq!(f.subcomponents_trimmed(1), vec!["a", "b"]);
+        assert_eq!(f.subcomponents_trimmed(0), vec!["x"]);
+        assert!(f.subcomponents_trimmed(5).is_empty());
+
+        let f = Field::parse_mandatory(Some("&&"), &d).unwrap();
+        assert!(f.subcomponents_trimmed(0).is_empty());
     }
 
     #[cfg(feature = "string_index")]
@@ -0,0 +1,108 @@
+use crate::{Field, Message, Segment};
+use serde_json::{json, Value};
+
+impl<'a> Message<'a> {
+    /// Converts this message into a JSON value, for consumption by web front-ends etc.  Requires the `serde` feature.
+    ///
+    /// The message is represented as an array of segments, each an object with the segment identifier as `type` and an array of `fields`.
+    /// Each field is always nested the same way, as an array of repeats, each an array of components, each an array of subcomponents (strings),
+    /// so the HL7 structure can be recovered from the JSON (eg `a~b` and `a^b` serialise differently).  A simple value `x` is therefore `[[["x"]]]`.
+    /// The identifier isn't repeated in `fields`, so the first entry is field 1 (or MSH-2 for the MSH, which is always a plain string as it defines the separators).
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::Message;
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let m = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3\rPID|||555-44-4444||EVERYWOMAN^EVE")?;
+    /// let json = m.to_json();
+    /// assert_eq!(json[1]["type"], "PID");
+    /// assert_eq!(json[1]["fields"][4][0][1][0], "EVE"); // PID-5, repeat 1, component 2, subcomponent 1
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_json(&self) -> Value {
+        Value::Array(self.segments.iter().map(segment_json).collect())
+    }
+
+    /// Converts this message into an indented JSON string, see [`Message::to_json()`].  Requires the `serde` feature.
+    pub fn to_json_pretty(&self) -> String {
+        serde_json::to_string_pretty(&self.to_json()).expect("Unable to serialise JSON value")
+    }
+}
+
+fn segment_json(segment: &Segment) -> Value {
+    let fields: Vec<Value> = segment
+        .fields
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, f)| {
+            // MSH-2 defines the separators, it isn't structured data
+            if i == 1 && segment.is_msh() {
+                Value::from(f.source)
+            } else {
+                field_json(f)
+            }
+        })
+        .collect();
+
+    json!({
+        "type": segment.identifier(),
+        "fields": fields,
+    })
+}
+
+/// Nests the field as repeats, then components, then subcomponents, regardless of how many values are at each level
+fn field_json(field: &Field) -> Value {
+    json!(field.subcomponents)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Hl7ParseError, Message};
+    use serde_json::json;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn ensure_ack_to_json() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|CATH|StJohn|AcmeHIS|StJohn|20061019172719||ACK^O01|MSGID12349876|P|2.3\rMSA|AA|MSGID12349876";
+        let msg = Message::try_from(hl7)?;
+        let json = msg.to_json();
+
+        assert_eq!(json[0]["type"], "MSH");
+        assert_eq!(json[0]["fields"][0], "^~\\&");
+        assert_eq!(json[0]["fields"][7][0][0][0], "ACK");
+        assert_eq!(json[1]["type"], "MSA");
+        assert_eq!(json[1]["fields"][0], json!([[["AA"]]]));
+
+        let pretty = msg.to_json_pretty();
+        assert!(pretty.contains("\"MSA\""));
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_nested_fields_to_json() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB\rPID|1|a~b^c&d";
+        let msg = Message::try_from(hl7)?;
+        let json = msg.to_json();
+
+        assert_eq!(json[1]["fields"][0], json!([[["1"]]]));
+        assert_eq!(json[1]["fields"][1], json!([[["a"]], [["b"], ["c", "d"]]]));
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_repeats_and_components_are_distinguished() -> Result<(), Hl7ParseError> {
+        let repeats = Message::try_from("MSH|^~\\&|GHH LAB\rPID|a~b")?.to_json();
+        let components = Message::try_from("MSH|^~\\&|GHH LAB\rPID|a^b")?.to_json();
+        let subcomponents = Message::try_from("MSH|^~\\&|GHH LAB\rPID|a&b")?.to_json();
+
+        assert_eq!(repeats[1]["fields"][0], json!([[["a"]], [["b"]]]));
+        assert_eq!(components[1]["fields"][0], json!([[["a"], ["b"]]]));
+        assert_eq!(subcomponents[1]["fields"][0], json!([[["a", "b"]]]));
+        assert_ne!(repeats, components);
+        assert_ne!(components, subcomponents);
+        Ok(())
+    }
+}
@@ -10,21 +10,44 @@ This crate tries to provide the tools to build HL7 systems without dictating _ho
 
 */
 
+mod assembler;
+mod dtm;
 mod escape_sequence;
 mod fields;
+#[cfg(feature = "serde")]
+mod json;
+mod line_ending;
 mod message;
+mod observation;
+pub mod prelude;
+mod query_path;
+mod segment_kind;
 mod segments;
 mod separators;
+mod structured_numeric;
+mod validation_issue;
+mod visitor;
+mod z_segment;
 
 // re-exports to simplify namespacing (#25)
-pub use fields::Field;
+pub use assembler::MessageAssembler;
+pub use dtm::{parse_dtm, DtmParts};
+pub use fields::{ComponentWhitespace, Field};
+pub use line_ending::LineEnding;
 pub use message::Message;
+pub use observation::{CodedElement, Observation};
+pub use query_path::QueryPath;
+pub use segment_kind::SegmentKind;
 pub use segments::Segment;
+pub use structured_numeric::StructuredNumeric;
+pub use z_segment::ZSegment;
 
-pub use escape_sequence::EscapeSequence;
-pub use separators::Separators;
+pub use escape_sequence::{EscapeSequence, EscaperCache};
+pub use separators::{MissingEncodingChars, Separators};
+pub use validation_issue::ValidationIssue;
+pub use visitor::Visitor;
 
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
 pub enum Hl7ParseError {
     #[error("Unexpected error: {0}")]
     Generic(String),
@@ -34,4 +57,30 @@ pub enum Hl7ParseError {
 
     #[error("Required value missing")]
     MissingRequiredValue(),
+
+    #[error("Invalid escape sequence: {0}")]
+    InvalidEscapeSequence(String),
+
+    #[error("Invalid query path {0}")]
+    InvalidQuery(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ensure_errors_can_be_compared() {
+        assert_eq!(
+            Hl7ParseError::MissingRequiredValue(),
+            Hl7ParseError::MissingRequiredValue()
+        );
+        assert_ne!(
+            Hl7ParseError::Generic("a".to_string()),
+            Hl7ParseError::Generic("b".to_string())
+        );
+
+        let err = Hl7ParseError::Msh1Msh2("Message doesn't start with 'MSH'".to_string());
+        assert_eq!(err.clone(), err);
+    }
 }
@@ -0,0 +1,66 @@
+use std::fmt::Display;
+
+/// The terminator used between segments in the source of a message.
+/// The spec requires a carriage return (`\r`), but messages that have been through text-based tooling often turn up with `\n` or `\r\n` instead.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum LineEnding {
+    /// A single carriage return (`\r`), as required by the spec
+    Cr,
+    /// A single line feed (`\n`)
+    Lf,
+    /// A carriage return followed by a line feed (`\r\n`)
+    CrLf,
+}
+
+impl LineEnding {
+    /// Detects the line ending used in the given source from the first terminator found.
+    /// Sources with no terminator at all (ie a single segment) are reported as [`LineEnding::Cr`].
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::LineEnding;
+    /// assert_eq!(LineEnding::detect("MSH|^~\\&|GHH LAB\r\nPID|1"), LineEnding::CrLf);
+    /// ```
+    pub fn detect(source: &str) -> LineEnding {
+        match source.find(['\r', '\n']) {
+            Some(i) if source[i..].starts_with("\r\n") => LineEnding::CrLf,
+            Some(i) if source[i..].starts_with('\n') => LineEnding::Lf,
+            _ => LineEnding::Cr,
+        }
+    }
+
+    /// Returns the terminator chars for this line ending
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Cr => "\r",
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+}
+
+impl Default for LineEnding {
+    /// The spec mandated carriage return
+    fn default() -> LineEnding {
+        LineEnding::Cr
+    }
+}
+
+impl Display for LineEnding {
+    /// Required for to_string() and other formatter consumers.  This returns the terminator chars.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ensure_line_endings_are_detected() {
+        assert_eq!(LineEnding::detect("MSH|^~\\&\rPID|1"), LineEnding::Cr);
+        assert_eq!(LineEnding::detect("MSH|^~\\&\nPID|1"), LineEnding::Lf);
+        assert_eq!(LineEnding::detect("MSH|^~\\&\r\nPID|1"), LineEnding::CrLf);
+        assert_eq!(LineEnding::detect("MSH|^~\\&"), LineEnding::Cr);
+    }
+}
@@ -1,13 +1,17 @@
 use super::segments::Segment;
 use super::separators::Separators;
 use super::*;
+use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::fmt::Display;
+use std::hash::{Hash, Hasher};
 use std::ops::Index;
 
 /// A Message is an entire HL7 message parsed into it's constituent segments, fields, repeats and subcomponents,
 /// and it consists of (1 or more) Segments.
-/// Message parses the source string into `&str` slices (minimising copying) and can be created using either the [`Message::new()`] function or `TryFrom::try_from()` impl.
+/// Message parses the source string into `&str` slices (minimising copying) and should be created using the `TryFrom::try_from()` impl.
 /// ## Example:
 /// ```
 /// # use rusthl7::Hl7ParseError;
@@ -15,8 +19,7 @@ use std::ops::Index;
 /// use std::convert::TryFrom;
 /// # fn main() -> Result<(), Hl7ParseError> {
 /// let source = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|1|Foo\rOBR|2|Bar";
-/// let m = Message::new(source); // Note that this method can panic
-/// let result = Message::try_from(source); // while try_from() returns a `Result`
+/// let result = Message::try_from(source); // try_from() returns a `Result` rather than panicking
 /// assert!(result.is_ok());
 /// # Ok(())
 /// # }
@@ -26,12 +29,15 @@ pub struct Message<'a> {
     source: &'a str,
     pub segments: Vec<Segment<'a>>,
     separators: Separators,
+    line_ending: LineEnding,
 }
 
 impl<'a> Message<'a> {
     /// Takes the source HL7 string and parses it into a message.  Segments
     /// and other data are slices (`&str`) into the source HL7 for minimal (preferably 0) copying.  
     /// ⚠ If an error occurs this method will panic (for back-compat reasons)!  For the preferred non-panicing alternative import the `std::convert::TryFrom` trait and use the `try_from()` function. ⚠
+    ///
+    /// This method is deprecated and will be removed in a future version.
     /// ## Example:
     /// ```
     /// # use rusthl7::Hl7ParseError;
@@ -42,326 +48,2171 @@ impl<'a> Message<'a> {
     /// # Ok(())
     /// # }
     /// ```
+    #[deprecated(
+        since = "0.7.0",
+        note = "This function panics on invalid input, use `Message::try_from()` instead."
+    )]
     pub fn new(source: &'a str) -> Message<'a> {
         Message::try_from(source).unwrap()
     }
 
     /// Queries for segments of the given type (i.e. matches by identifier, or name), returning a set of 0 or more segments.
+    ///
+    /// This method never actually returns an error, and is deprecated in favour of the infallible [`Message::segments_named()`].
+    #[deprecated(
+        since = "0.7.0",
+        note = "This function never errors, use the infallible `segments_named()` instead."
+    )]
+    pub fn segments_by_identifier(&self, name: &str) -> Result<Vec<&Segment<'a>>, Hl7ParseError> {
+        Ok(self.segments_named(name))
+    }
+
+    /// Queries for segments of the given type (i.e. matches by identifier, or name), returning a set of 0 or more segments.
+    /// The identifier must match exactly, including case (see [`Segment::identifier()`]).
     /// ## Example:
     /// ```
     /// # use rusthl7::Hl7ParseError;
     /// # use rusthl7::Message;
+    /// # use std::convert::TryFrom;
     /// # fn main() -> Result<(), Hl7ParseError> {
     /// let source = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|1|Foo\rOBR|2|Bar";
-    /// let m = Message::new(source);
-    /// let obr_segments = m.segments_by_identifier("OBR")?;
+    /// let m = Message::try_from(source)?;
+    /// let obr_segments = m.segments_named("OBR");
     /// assert_eq!(obr_segments.len(), 2);
     /// # Ok(())
     /// # }
     /// ```
-    pub fn segments_by_identifier(&self, name: &str) -> Result<Vec<&Segment<'a>>, Hl7ParseError> {
-        let found: Vec<&Segment<'a>> = self
-            .segments
+    pub fn segments_named(&self, name: &str) -> Vec<&Segment<'a>> {
+        self.segments
             .iter()
             .filter(|s| s.identifier() == name)
-            .collect();
-        Ok(found)
+            .collect()
     }
 
-    /// Present input vectors of &generics to vectors of &str
-    pub fn segments_to_str_vecs(
-        segments: Vec<&'a Segment<'a>>,
-    ) -> Result<Vec<Vec<&'a str>>, Hl7ParseError> {
-        let vecs = segments
+    /// Gets the value of the given field from the first segment with a matching identifier (including case), or `None` if either the segment or field don't exist.
+    /// Fields are numbered from 1 in the same way as [`Message::query()`], with index 0 returning the segment identifier.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::Message;
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let m = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rPID|||555-44-4444")?;
+    /// assert_eq!(m.get_field("PID", 3), Some("555-44-4444"));
+    /// assert_eq!(m.get_field("PID", 30), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_field(&self, segment: &str, field_index: usize) -> Option<&'a str> {
+        self.segments
             .iter()
-            .map(|s| s.fields.iter().map(|f| f.as_str()).collect())
-            .collect();
-
-        Ok(vecs)
+            .find(|s| s.identifier() == segment)
+            .and_then(|s| s.field_value(field_index))
     }
 
-    /// Returns the source string slice used to create this Message initially.  This method does not allocate.
+    /// Resolves a `SEGMENT.Fn` path (eg `PID.F5`) to the [`Field`] itself in the first matching segment, rather than it's string value,
+    /// so callers can inspect the repeats/components without re-parsing.  Returns `None` if the path isn't of that form, or doesn't exist in this message.
+    /// Fields are numbered in the same way as [`Message::query()`], including `F0` for the segment identifier.
     /// ## Example:
     /// ```
     /// # use rusthl7::Hl7ParseError;
     /// # use rusthl7::Message;
     /// # use std::convert::TryFrom;
     /// # fn main() -> Result<(), Hl7ParseError> {
-    /// let source = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4";
-    /// let m = Message::try_from(source)?;
-    /// assert_eq!(source, m.as_str());
+    /// let m = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3\rPID|||555-44-4444||EVERYWOMAN^EVE^E")?;
+    /// let name = m.field("PID.F5").unwrap();
+    /// assert_eq!(name.components[0], vec!["EVERYWOMAN", "EVE", "E"]);
     /// # Ok(())
     /// # }
     /// ```
-    #[inline]
-    pub fn as_str(&self) -> &'a str {
-        self.source
-    }
+    pub fn field(&self, segment_path: &str) -> Option<&Field<'a>> {
+        let path = QueryPath::parse(segment_path).ok()?;
+        if path.repeat.is_some() || path.component.is_some() || path.subcomponent.is_some() {
+            return None;
+        }
 
-    /// Gets the delimiter information for this Message.  
-    /// Remember that in HL7 _each individual message_ can have unique characters as separators between fields, repeats, components and sub-components, and so this is a per-message value.
-    /// This method does not allocate
-    pub fn get_separators(&self) -> Separators {
-        self.separators
+        let segment = self
+            .segments
+            .iter()
+            .find(|s| s.identifier() == path.segment)?;
+        segment.fields.get(segment.field_position(path.field?)?)
     }
 
-    /// Access Segment, Field, or sub-field string references by string index
-    pub fn query<'b, S>(&self, idx: S) -> &'a str
-    where
-        S: Into<&'b str>,
-    {
-        let idx = idx.into();
+    /// Counts the repeats of the field at the given `SEGMENT.Fn` path (eg `PID.F13`) in the first matching segment, which is handy before iterating them.
+    /// Returns 0 if the field doesn't exist or is empty.  As per [`Message::query()`], MSH-2 is never split so is always a single repeat.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::Message;
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let m = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3\rPID|||555-44-4444~555-44-5555")?;
+    /// assert_eq!(m.repeat_count("PID.F3"), 2);
+    /// assert_eq!(m.repeat_count("PID.F4"), 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn repeat_count(&self, segment_field_path: &str) -> usize {
+        let field = match self.field(segment_field_path) {
+            Some(field) => field,
+            None => return 0,
+        };
 
-        // Parse index elements
-        let indices = Self::parse_query_string(idx);
-        let seg_name = indices[0];
-        // Find our first segment without offending the borow checker
-        let seg_index = self
+        let is_msh2 = self
             .segments
-            .iter()
-            .position(|r| &r.as_str()[..seg_name.len()] == seg_name)
-            .expect("Segment not found");
-        let seg = &self.segments[seg_index];
-        if indices.len() < 2 {
-            seg.source
+            .first()
+            .filter(|s| s.is_msh())
+            .and_then(|s| s.fields.get(1))
+            .is_some_and(|msh2| std::ptr::eq(msh2, field));
+
+        if field.source.is_empty() {
+            0
+        } else if is_msh2 {
+            1
         } else {
-            let query = indices[1..].join(".");
-            seg.query(&*query)
+            field.repeats.len()
         }
     }
 
-    /// Parse query/index string to fill-in missing values.
-    /// Required when conumer requests "PID.F3.C1" to pass integers down
-    /// to the usize indexers at the appropriate positions
-    fn parse_query_string(query: &str) -> Vec<&str> {
-        fn query_idx_pos(indices: &[&str], idx: &str) -> Option<usize> {
-            indices[1..]
-                .iter()
-                .position(|r| r[0..1].to_uppercase() == idx)
-        }
-        let indices: Vec<&str> = query.split('.').collect();
-        // Leave segment name untouched - complex match
-        let mut res = vec![indices[0]];
-        // Get segment positions, if any
-        let sub_pos = query_idx_pos(&indices, "S");
-        let com_pos = query_idx_pos(&indices, "C");
-        let rep_pos = query_idx_pos(&indices, "R");
-        let fld_pos = query_idx_pos(&indices, "F");
-        // Push segment values to result, returning early if possible
-        match fld_pos {
-            Some(f) => res.push(indices[f + 1]),
-            None => {
-                // If empty but we have subsections, default to F1
-                if rep_pos.is_some() || com_pos.is_some() || sub_pos.is_some() {
-                    res.push("F1")
-                } else {
-                    return res;
-                }
-            }
-        };
-        match rep_pos {
-            Some(r) => res.push(indices[r + 1]),
-            None => {
-                // If empty but we have subsections, default to R1
-                if com_pos.is_some() || sub_pos.is_some() {
-                    res.push("R1")
-                } else {
-                    return res;
-                }
-            }
-        };
-        match com_pos {
-            Some(c) => res.push(indices[c + 1]),
-            None => {
-                // If empty but we have a subcomponent, default to C1
-                if sub_pos.is_some() {
-                    res.push("C1")
-                } else {
-                    return res;
-                }
-            }
-        };
-        if let Some(s) = sub_pos {
-            res.push(indices[s + 1])
-        }
-        res
-    }
-}
+    /// Joins the given field from the first run of consecutive segments with a matching identifier into a single string, separated by `sep`.
+    /// This is useful for free text (eg NTE-3) which is frequently split across several segments that belong together.
+    /// Fields are numbered in the same way as [`Message::get_field()`], and segments missing the field contribute an empty string.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::Message;
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let m = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3\rOBX|1|TX\rNTE|1||First line\rNTE|2||Second line")?;
+    /// assert_eq!(m.concatenate_text("NTE", 3, "\n"), "First line\nSecond line");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn concatenate_text(&self, name: &str, field: usize, sep: &str) -> String {
+        let values: Vec<&str> = self
+            .segments
+            .iter()
+            .skip_while(|s| s.identifier() != name)
+            .take_while(|s| s.identifier() == name)
+            .map(|s| s.field_value(field).unwrap_or(""))
+            .collect();
 
-impl<'a> TryFrom<&'a str> for Message<'a> {
-    type Error = Hl7ParseError;
+        values.join(sep)
+    }
 
-    /// Takes the source HL7 string and parses it into a message.  Segments
-    /// and other data are slices (`&str`) into the source HL7 for minimal (preferably 0) copying.
+    /// Returns true if this message is an acknowledgement, ie the message code in MSH-9 is `ACK`.
     /// ## Example:
     /// ```
     /// # use rusthl7::Hl7ParseError;
     /// # use rusthl7::Message;
     /// # use std::convert::TryFrom;
     /// # fn main() -> Result<(), Hl7ParseError> {
-    /// let m = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4")?;
+    /// let m = Message::try_from("MSH|^~\\&|CATH|StJohn|AcmeHIS|StJohn|20061019172719||ACK^O01|MSGID12349876|P|2.3\rMSA|AA|MSGID12349876")?;
+    /// assert!(m.is_ack());
+    /// assert!(!m.is_nack());
+    /// assert_eq!(m.ack_code(), Some("AA"));
     /// # Ok(())
     /// # }
     /// ```
-    fn try_from(source: &'a str) -> Result<Self, Self::Error> {
-        let separators = str::parse::<Separators>(source)?;
-
-        let possible = source
-            .split(separators.segment)
-            .map(|line| Segment::parse(line, &separators));
+    pub fn is_ack(&self) -> bool {
+        // MSH-9 is at index 8 as MSH-1 (the field separator) isn't a split field
+        // `segments` is public so may have been emptied since parsing
+        match self.segments.first().and_then(|s| s.fields.get(8)) {
+            Some(message_type) => message_type[(0, 0)] == "ACK",
+            None => false,
+        }
+    }
 
-        let segments: Vec<Segment> = possible.collect::<Result<Vec<Segment>, Self::Error>>()?;
+    /// Returns true if this message is an acknowledgement carrying an error or reject code (`AE`/`AR`/`CE`/`CR`) in MSA-1.
+    pub fn is_nack(&self) -> bool {
+        self.is_ack() && matches!(self.ack_code(), Some("AE" | "AR" | "CE" | "CR"))
+    }
 
-        let m = Message {
-            source,
-            segments,
-            separators,
-        };
+    /// Returns the acknowledgement code (eg `AA`, `AE`, `AR`) from MSA-1, or `None` if there's no MSA segment.
+    pub fn ack_code(&self) -> Option<&'a str> {
+        self.get_field("MSA", 1)
+    }
 
-        Ok(m)
+    /// Returns the identifiers of each segment in this message, in document order.  This gives a quick view of the "shape" of a message.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::Message;
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let source = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|1|Foo\rOBR|2|Bar";
+    /// let m = Message::try_from(source)?;
+    /// assert_eq!(m.segment_names(), vec!["MSH", "OBR", "OBR"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn segment_names(&self) -> Vec<&'a str> {
+        self.segments.iter().map(|s| s.identifier()).collect()
     }
-}
 
-impl<'a> Display for Message<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.source)
+    /// Returns all custom (`Z`) segments in this message, in document order, ignoring the standard segments.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::Message;
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let source = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rZPD|1|Foo\rOBR|2|Bar";
+    /// let m = Message::try_from(source)?;
+    /// let z_segments = m.z_segments();
+    /// assert_eq!(z_segments.len(), 1);
+    /// assert_eq!(z_segments[0].query("F2"), "Foo");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn z_segments(&self) -> Vec<ZSegment<'a>> {
+        self.segments
+            .iter()
+            .filter(|s| ZSegment::is_z_segment(s))
+            .cloned()
+            .filter_map(|s| ZSegment::try_from(s).ok())
+            .collect()
     }
-}
 
-impl<'a> Clone for Message<'a> {
-    /// Creates a new cloned Message object referencing the same source slice as the original.
+    /// Reads every OBX segment in this message as an [`Observation`], in document order.  This is a common first step when processing lab results.
     /// ## Example:
     /// ```
     /// # use rusthl7::Hl7ParseError;
     /// # use rusthl7::Message;
     /// # use std::convert::TryFrom;
     /// # fn main() -> Result<(), Hl7ParseError> {
-    /// let m = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4")?;
-    /// let cloned = m.clone(); // this object is looking at the same string slice as m
+    /// let m = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3\rOBX|1|NM|2345-7^GLUCOSE^LN||182|mg/dl|70-105|H")?;
+    /// let observations = m.observations();
+    /// assert_eq!(observations[0].code.text, "GLUCOSE");
+    /// assert_eq!(observations[0].value, "182");
+    /// assert_eq!(observations[0].abnormal_flag, Some("H"));
     /// # Ok(())
     /// # }
     /// ```
-    fn clone(&self) -> Self {
-        Message::try_from(self.source).unwrap()
+    pub fn observations(&self) -> Vec<Observation<'a>> {
+        self.segments_named("OBX")
+            .into_iter()
+            .map(Observation::from_segment)
+            .collect()
     }
-}
 
-impl<'a> Index<usize> for Message<'a> {
-    type Output = &'a str;
+    /// Walks every segment, field and component in this message in document order, calling back to the given [`Visitor`].
+    /// This saves consumers (validators, exporters etc) from re-implementing the nested loops themselves.
+    pub fn walk<V: Visitor<'a>>(&self, visitor: &mut V) {
+        for segment in &self.segments {
+            visitor.visit_segment(segment);
 
-    /// Access Segment string reference by numeric index
-    fn index(&self, idx: usize) -> &Self::Output {
-        if idx > self.segments.len() {
-            return &"";
+            for field in segment.fields.iter().skip(1) {
+                visitor.visit_field(field);
+
+                for component in field.components.iter().flatten() {
+                    visitor.visit_component(component);
+                }
+            }
         }
-        &self.segments[idx].source
     }
-}
-#[cfg(feature = "string_index")]
-impl<'a> Index<String> for Message<'a> {
-    type Output = &'a str;
 
-    /// Access Segment, Field, or sub-field string references by string index
-    #[cfg(feature = "string_index")]
-    fn index(&self, idx: String) -> &Self::Output {
-        // Parse index elements
-        let indices = Self::parse_query_string(&idx);
-        let seg_name = indices[0];
-        // Find our first segment without offending the borow checker
-        let seg_index = self
+    /// Builds the HL7 for a reduced copy of this message, keeping only the segments matching the given predicate.
+    /// The MSH segment is always kept.  The result can be parsed with `Message::try_from()` as required.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::Message;
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let m = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3\rOBR|1|Foo\rNTE|1||Comment")?;
+    /// let reduced = m.retain_segments(|s| s.identifier() != "NTE");
+    /// assert_eq!(reduced, "MSH|^~\\&|GHH LAB|ELAB-3\rOBR|1|Foo");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn retain_segments<F>(&self, predicate: F) -> String
+    where
+        F: Fn(&Segment<'a>) -> bool,
+    {
+        let retained: Vec<&str> = self
             .segments
             .iter()
-            .position(|r| &r.as_str()[..seg_name.len()] == seg_name)
-            .expect("Segment not found");
-        let seg = &self.segments[seg_index];
-        if indices.len() < 2 {
-            &seg.source
-        } else {
-            &seg[indices[1..].join(".")]
-        }
+            .filter(|s| s.is_msh() || predicate(s))
+            .map(|s| s.source)
+            .collect();
+        retained.join(self.line_ending.as_str())
     }
-}
 
-#[cfg(feature = "string_index")]
-impl<'a> Index<&str> for Message<'a> {
-    type Output = &'a str;
+    /// Renders an indented outline of this message's segments, fields, repeats, components and subcomponents, for debugging.
+    /// Each level is only expanded when there's more than one item in it, and items are numbered as per [`Message::query()`].
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::Message;
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let m = Message::try_from("MSH|^~\\&|GHH LAB\rPID|||555-44-4444~555-44-5555||EVERYWOMAN^EVE")?;
+    /// println!("{}", m.debug_tree());
+    /// // MSH
+    /// //   F2: ^~\&
+    /// //   F3: GHH LAB
+    /// // PID
+    /// //   F1:
+    /// //   F2:
+    /// //   F3: 555-44-4444~555-44-5555
+    /// //     R1: 555-44-4444
+    /// //     R2: 555-44-5555
+    /// //   F4:
+    /// //   F5: EVERYWOMAN^EVE
+    /// //     C1: EVERYWOMAN
+    /// //     C2: EVE
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn debug_tree(&self) -> String {
+        let mut tree = String::new();
 
-    #[cfg(feature = "string_index")]
-    fn index(&self, idx: &str) -> &Self::Output {
-        &self[String::from(idx)]
-    }
-}
+        for segment in &self.segments {
+            tree.push_str(segment.identifier());
+            tree.push('\n');
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+            let is_msh = segment.is_msh();
+            for (f, field) in segment.fields.iter().enumerate().skip(1) {
+                // MSH-1 isn't split out as a field, so MSH-2 is at position 1
+                let number = if is_msh { f + 1 } else { f };
+                push_tree_line(&mut tree, 1, 'F', number, field.source);
 
-    #[test]
-    fn ensure_segments_are_returned() -> Result<(), Hl7ParseError> {
-        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|segment";
-        let msg = Message::try_from(hl7)?;
+                // the MSH encoding chars contain the separators, so there's nothing useful to expand
+                if is_msh && number == 2 {
+                    continue;
+                }
 
-        assert_eq!(msg.segments.len(), 2);
-        Ok(())
-    }
+                let repeat_indent = if field.repeats.len() > 1 { 3 } else { 2 };
+                for (r, repeat) in field.repeats.iter().enumerate() {
+                    if field.repeats.len() > 1 {
+                        push_tree_line(&mut tree, 2, 'R', r + 1, repeat);
+                    }
 
-    #[test]
-    fn ensure_missing_segments_are_not_found() -> Result<(), Hl7ParseError> {
-        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|segment";
-        let msg = Message::try_from(hl7)?;
+                    let components = &field.components[r];
+                    if components.len() < 2 {
+                        continue;
+                    }
+                    for (c, component) in components.iter().enumerate() {
+                        push_tree_line(&mut tree, repeat_indent, 'C', c + 1, component);
+
+                        let subcomponents = &field.subcomponents[r][c];
+                        if subcomponents.len() < 2 {
+                            continue;
+                        }
+                        for (s, subcomponent) in subcomponents.iter().enumerate() {
+                            push_tree_line(&mut tree, repeat_indent + 1, 'S', s + 1, subcomponent);
+                        }
+                    }
+                }
+            }
+        }
+
+        tree
+    }
+
+    /// Present input vectors of &generics to vectors of &str
+    pub fn segments_to_str_vecs(
+        segments: Vec<&'a Segment<'a>>,
+    ) -> Result<Vec<Vec<&'a str>>, Hl7ParseError> {
+        let vecs = segments
+            .iter()
+            .map(|s| s.fields.iter().map(|f| f.as_str()).collect())
+            .collect();
+
+        Ok(vecs)
+    }
+
+    /// Returns the source string slice used to create this Message initially.  This method does not allocate.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::Message;
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let source = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4";
+    /// let m = Message::try_from(source)?;
+    /// assert_eq!(source, m.as_str());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn as_str(&self) -> &'a str {
+        self.source
+    }
+
+    /// Gets the delimiter information for this Message.  
+    /// Remember that in HL7 _each individual message_ can have unique characters as separators between fields, repeats, components and sub-components, and so this is a per-message value.
+    /// This method does not allocate
+    pub fn get_separators(&self) -> Separators {
+        self.separators
+    }
+
+    /// Returns the raw MSH-2 encoding characters exactly as they appear in the source (eg `^~\&`), useful for echoing verbatim into an ACK.
+    /// Unlike re-building this from [`Message::get_separators()`], any extra chars sent by later versions of the spec are preserved.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::Message;
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let m = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4")?;
+    /// assert_eq!(m.encoding_characters(), "^~\\&");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn encoding_characters(&self) -> &'a str {
+        // MSH-2 is at index 1 as MSH-1 (the field separator) isn't a split field
+        self.segments
+            .first()
+            .and_then(|s| s.fields.get(1))
+            .map(|f| f.source)
+            .unwrap_or("")
+    }
+
+    /// Returns true if the repeat separator char is used anywhere in this message (outside of the MSH-2 encoding characters).
+    /// Performance-sensitive callers can use this to skip handling of field repeats entirely.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::Message;
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let m = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3\rPID|||555-44-4444~555-44-5555")?;
+    /// assert!(m.uses_repeats());
+    /// assert!(!m.uses_escapes());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn uses_repeats(&self) -> bool {
+        self.content_after_encoding_chars()
+            .contains(self.separators.repeat)
+    }
+
+    /// Returns true if the escape char is used anywhere in this message (outside of the MSH-2 encoding characters).
+    /// Performance-sensitive callers can use this to skip escape sequence decoding entirely.
+    pub fn uses_escapes(&self) -> bool {
+        self.content_after_encoding_chars()
+            .contains(self.separators.escape_char)
+    }
+
+    /// Returns the source from the end of MSH-2 onwards, so the encoding chars themselves aren't considered when scanning for separator usage.
+    fn content_after_encoding_chars(&self) -> &'a str {
+        let field = self.separators.field;
+        // skip `MSH` and the field separator, then the encoding chars up to the next field separator
+        let start = 3 + field.len_utf8();
+        let rest = match self.source.get(start..) {
+            Some(rest) => rest,
+            None => return "", // too short to hold any more than the header
+        };
+        match rest.find(field) {
+            Some(end) => &rest[end..],
+            None => "",
+        }
+    }
+
+    /// Gets the terminator actually used between segments in the source of this message.
+    /// The spec requires `\r`, but `\n` and `\r\n` are tolerated when parsing, and this allows the message to be faithfully re-serialised.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::{LineEnding, Message};
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let m = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3\r\nOBR|1|Foo")?;
+    /// assert_eq!(m.detected_line_ending(), LineEnding::CrLf);
+    /// assert_eq!(m.query("OBR.F2"), "Foo");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn detected_line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    /// Decomposes this Message into it's source slice, parsed segments and separators, without re-parsing or copying.
+    /// This is intended for consumers who want to take ownership of the parsed representation to build their own structures.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::Message;
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let source = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|1|Foo";
+    /// let m = Message::try_from(source)?;
+    /// let (src, segments, separators) = m.into_parts();
+    /// assert_eq!(source, src);
+    /// assert_eq!(2, segments.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_parts(self) -> (&'a str, Vec<Segment<'a>>, Separators) {
+        (self.source, self.segments, self.separators)
+    }
+
+    /// Rebuilds a Message from the parts returned by [`Message::into_parts()`].
+    /// The first segment must be an `MSH`, otherwise an `Err(Hl7ParseError::Msh1Msh2)` is returned.
+    /// The source must also start with that MSH segment (as methods such as [`Message::uses_repeats()`] scan the source directly), otherwise an `Err(Hl7ParseError::Generic)` is returned.
+    /// Note that no further checks are made that the remaining segments actually came from the given source.
+    pub fn from_parts(
+        source: &'a str,
+        segments: Vec<Segment<'a>>,
+        separators: Separators,
+    ) -> Result<Message<'a>, Hl7ParseError> {
+        match segments.first() {
+            Some(s) if s.is_msh() && source.starts_with(s.source) => Ok(Message {
+                source,
+                segments,
+                separators,
+                line_ending: LineEnding::detect(source),
+            }),
+            Some(s) if s.is_msh() => Err(Hl7ParseError::Generic(
+                "Source doesn't start with the 'MSH' segment".to_string(),
+            )),
+            _ => Err(Hl7ParseError::Msh1Msh2(
+                "First segment is not an 'MSH'".to_string(),
+            )),
+        }
+    }
+
+    /// Parses the source HL7 leniently, papering over recoverable problems rather than failing, and reporting each one as a [`ValidationIssue`].
+    /// Currently this tolerates:
+    /// - fewer than four encoding chars in MSH-2 (the defaults are used for the missing ones)
+    /// - blank lines between segments (which are skipped)
+    /// - trailing control chars such as NUL padding (which are ignored)
+    ///
+    /// An `Err` is still returned for problems that can't be recovered from, such as the source not starting with an `MSH`.
+    /// Parsing doesn't check that required fields are present, see [`Message::validate()`] for that.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::{Message, ValidationIssue};
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let (m, issues) = Message::parse_lenient("MSH|^~|GHH LAB|ELAB-3\r\rPID|||555-44-4444")?;
+    /// assert_eq!(m.query("PID.F3"), "555-44-4444");
+    /// assert_eq!(issues, vec![ValidationIssue::MissingEncodingChars, ValidationIssue::EmptySegment(1)]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn parse_lenient(
+        source: &'a str,
+    ) -> Result<(Message<'a>, Vec<ValidationIssue>), Hl7ParseError> {
+        let mut issues = Vec::new();
+
+        let separators = match Separators::parse_with(source, MissingEncodingChars::Error) {
+            Ok(separators) => separators,
+            Err(_) => {
+                let separators = Separators::parse_with(source, MissingEncodingChars::UseDefaults)?;
+                issues.push(ValidationIssue::MissingEncodingChars);
+                separators
+            }
+        };
+
+        let content = source.trim_end_matches(|c: char| c.is_control() && c != '\r' && c != '\n');
+        if content.len() != source.len() {
+            issues.push(ValidationIssue::TrailingControlChars);
+        }
+
+        let line_ending = LineEnding::detect(content);
+        let lines: Vec<&str> = content.split(line_ending.as_str()).collect();
+
+        let mut segments = Vec::with_capacity(lines.len());
+        for (line_number, line) in lines.iter().enumerate() {
+            if line.is_empty() {
+                // a single trailing terminator is common (and harmless), so isn't reported
+                if line_number != lines.len() - 1 {
+                    issues.push(ValidationIssue::EmptySegment(line_number));
+                }
+                continue;
+            }
+            segments.push(Segment::parse(*line, &separators)?);
+        }
+
+        let m = Message {
+            source,
+            segments,
+            separators,
+            line_ending,
+        };
+
+        Ok((m, issues))
+    }
+
+    /// Checks this message is well formed beyond what's needed to parse it, returning a [`ValidationIssue`] for each problem found (or an empty `Vec` if there are none).
+    /// Currently this checks the fields the base standard requires in every MSH are present: MSH-9 (message type), MSH-10 (control id),
+    /// MSH-11 (processing id) and MSH-12 (version id).  This is useful after [`Message::parse_lenient()`], or `Message::try_from()`, neither of which check them.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::{Message, ValidationIssue};
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let m = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456||2.4")?;
+    /// assert_eq!(m.validate(), vec![ValidationIssue::MissingMshField(11)]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        // parsing guarantees the MSH is first, but `segments` is public so may have been changed since
+        let msh = self.segments.first().filter(|s| s.is_msh());
+
+        (9..=12)
+            .filter(|&field| {
+                msh.and_then(|s| s.field_value(field))
+                    .unwrap_or("")
+                    .is_empty()
+            })
+            .map(ValidationIssue::MissingMshField)
+            .collect()
+    }
+
+    /// Lazily parses the segments of the given source one at a time, rather than materialising the whole message as `Message::try_from()` does.
+    /// This keeps peak memory down when streaming through very large messages (eg an ORU with thousands of OBX segments).
+    /// The separators are read from the MSH first, and if that fails a single `Err` is yielded.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::Message;
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let source = "MSH|^~\\&|GHH LAB|ELAB-3\rOBX|1|SN\rOBX|2|SN";
+    /// for segment in Message::iter_raw_segments(source) {
+    ///     let segment = segment?;
+    ///     assert!(segment.identifier() == "MSH" || segment.identifier() == "OBX");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter_raw_segments(
+        source: &'a str,
+    ) -> impl Iterator<Item = Result<Segment<'a>, Hl7ParseError>> + 'a {
+        let (separators, error) = match str::parse::<Separators>(source) {
+            Ok(separators) => (separators, None),
+            Err(e) => (Separators::DEFAULT, Some(e)),
+        };
+
+        // as per `try_from()`, trailing control chars are ignored and any of the common line endings are accepted
+        let lines = match error {
+            Some(_) => None,
+            None => {
+                let content =
+                    source.trim_end_matches(|c: char| c.is_control() && c != '\r' && c != '\n');
+                let line_ending = LineEnding::detect(content).as_str();
+                let content = content.strip_suffix(line_ending).unwrap_or(content);
+                Some(content.split(line_ending))
+            }
+        };
+
+        error.map(Err).into_iter().chain(
+            lines
+                .into_iter()
+                .flatten()
+                .map(move |line| Segment::parse(line, &separators)),
+        )
+    }
+
+    /// Access Segment, Field, or sub-field string references by string index.
+    ///
+    /// The query path follows this grammar (prefix letters are case-insensitive, and each may appear at most once):
+    /// ```text
+    /// path    := segment ("." part)*
+    /// segment := the segment identifier, eg "PID" (matched exactly, including case)
+    /// part    := ("F" | "R" | "C" | "S") digits
+    /// ```
+    /// where `F`, `R`, `C` and `S` select the field, repeat, component and subcomponent respectively.
+    /// Paths that don't match this grammar (eg `PID.3`, missing the `F` prefix, or `PID..F3`, with an empty part) return an empty string rather than guessing at the intent.
+    /// Whitespace around each part of the path is ignored, so `" PID .F5"` is equivalent to `"PID.F5"`, as is a trailing `.` (eg `"PID.F5."`).
+    ///
+    /// A bare segment identifier (eg `OBR`) returns the raw source of the whole segment.  Only the first matching segment is considered
+    /// (see [`Message::query_all_segments()`] for repeating segments), and if there's no matching segment an empty string is returned.
+    ///
+    /// MSH fields follow the spec numbering, so `MSH.F1` returns the field separator, `MSH.F2` the encoding chars and `MSH.F9.C1` the message code.
+    /// As MSH-2 defines the separators rather than holding structured data, it's never split: `MSH.F2` and `MSH.F2.R1` both return it whole, and any other path into it (eg `MSH.F2.C1`) returns an empty string.
+    ///
+    /// Segment identifiers never contain a `.`, so splitting the path on `.` is unambiguous.  Note however that the _returned value_ may well contain `.` chars,
+    /// and should not be fed back in as part of a query path.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::Message;
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let m = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rPID|||555-44-4444")?;
+    /// assert_eq!(m.query("PID.F3"), "555-44-4444");
+    /// assert_eq!(m.query("PID.3"), ""); // no prefix, ambiguous
+    /// assert_eq!(m.query("PID"), "PID|||555-44-4444"); // the whole segment
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query<'b, S>(&self, idx: S) -> &'a str
+    where
+        S: Into<&'b str>,
+    {
+        let idx = Self::trim_query_path(idx.into());
+
+        if !Self::is_valid_query_path(&idx) {
+            return "";
+        }
+
+        // Parse index elements
+        let indices = Self::parse_query_string(&idx);
+        let seg_name = indices[0];
+        let seg = match self.segments.iter().find(|s| s.identifier() == seg_name) {
+            Some(seg) => seg,
+            None => return "",
+        };
+        if indices.len() < 2 {
+            seg.source
+        } else {
+            seg.query_parts(&indices[1..])
+        }
+    }
+
+    /// Evaluates many paths (see [`Message::query()`]) in one call, returning the values in the same order as the paths.
+    /// The segments are only scanned once for all paths, rather than once per path, which helps when evaluating dozens of paths against each message (eg in a rules engine).
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::Message;
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let m = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rPID|||555-44-4444||EVERYWOMAN^EVE")?;
+    /// let values = m.query_many(&["MSH.F10", "PID.F3", "PID.F5.C2", "EVN.F1"]);
+    /// assert_eq!(values, vec!["CNTRL-3456", "555-44-4444", "EVE", ""]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query_many<'p>(&self, paths: &'p [&'p str]) -> Vec<&'a str> {
+        // the first segment of each type, as that's what `query()` resolves against
+        let mut first_segments: HashMap<&str, &Segment<'a>> = HashMap::new();
+        for segment in &self.segments {
+            first_segments
+                .entry(segment.identifier())
+                .or_insert(segment);
+        }
+
+        paths
+            .iter()
+            .map(|path| {
+                let idx = Self::trim_query_path(path);
+                if !Self::is_valid_query_path(&idx) {
+                    return "";
+                }
+
+                let indices = Self::parse_query_string(&idx);
+                match first_segments.get(indices[0]) {
+                    Some(seg) if indices.len() < 2 => seg.source,
+                    Some(seg) => seg.query_parts(&indices[1..]),
+                    None => "",
+                }
+            })
+            .collect()
+    }
+
+    /// As per [`Message::query()`], but distinguishes a value that is present but empty (`Some("")`) from one that doesn't exist at all (`None`).
+    /// `None` is also returned for invalid paths, or if there's no segment with the given identifier.  See [`Field::try_query()`].
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::Message;
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let m = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3\rOBX|1|a^^c")?;
+    /// assert_eq!(m.try_query("OBX.F2.C2"), Some(""));
+    /// assert_eq!(m.try_query("OBX.F2.C9"), None);
+    /// assert_eq!(m.try_query("PID.F3"), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn try_query<'b, S>(&self, idx: S) -> Option<&'a str>
+    where
+        S: Into<&'b str>,
+    {
+        let idx = Self::trim_query_path(idx.into());
+
+        if !Self::is_valid_query_path(&idx) {
+            return None;
+        }
+
+        let indices = Self::parse_query_string(&idx);
+        let seg = self
+            .segments
+            .iter()
+            .find(|s| s.identifier() == indices[0])?;
+        if indices.len() < 2 {
+            Some(seg.source)
+        } else {
+            seg.try_query_parts(&indices[1..])
+        }
+    }
+
+    /// As per [`Message::query()`], but the path is strictly validated first (see [`QueryPath::parse()`]), returning an `Err(Hl7ParseError::InvalidQuery)`
+    /// describing the problem rather than quietly returning an empty string.  A valid path that doesn't exist in this message returns `Ok("")`.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::Message;
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let m = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3\rPID|||555-44-4444")?;
+    /// assert_eq!(m.try_query_validated("PID.F3")?, "555-44-4444");
+    /// assert!(m.try_query_validated("PID.X3").is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn try_query_validated(&self, path: &str) -> Result<&'a str, Hl7ParseError> {
+        QueryPath::parse(path)?;
+        Ok(self.try_query(path).unwrap_or(""))
+    }
+
+    /// Access a field, component or subcomponent using a terse, purely numeric path of the form `SEG.field[.component[.subcomponent]]`, all 1-based (eg `PID.5.1`).
+    /// This always reads from the first repeat of the field, use [`Message::query()`] for access to repeats.
+    /// Malformed paths, or paths that don't exist in the message, return an empty string.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::Message;
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let m = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3\rPID|||555-44-4444||EVERYWOMAN^EVE^E")?;
+    /// assert_eq!(m.query_numeric("PID.5.1"), "EVERYWOMAN");
+    /// assert_eq!(m.query_numeric("PID.3"), "555-44-4444");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query_numeric(&self, path: &str) -> &'a str {
+        let mut parts = path.split('.');
+        let seg_name = parts.next().unwrap_or("");
+
+        let indices: Option<Vec<usize>> = parts.map(|p| p.parse::<usize>().ok()).collect();
+        let indices = match indices {
+            Some(i) if !i.is_empty() && i.len() <= 3 && !i[1..].contains(&0) => i,
+            _ => return "",
+        };
+
+        let segment = match self.segments.iter().find(|s| s.identifier() == seg_name) {
+            Some(segment) => segment,
+            None => return "",
+        };
+        if indices.len() == 1 {
+            return segment.field_value(indices[0]).unwrap_or("");
+        }
+
+        let field = match segment
+            .field_position(indices[0])
+            .filter(|_| !segment.is_msh_encoding_chars(indices[0]))
+            .and_then(|p| segment.fields.get(p))
+        {
+            Some(field) => field,
+            None => return "",
+        };
+
+        match indices[..] {
+            [_, c] => field[(0, c - 1)],
+            [_, c, s] => field[(0, c - 1, s - 1)],
+            _ => "",
+        }
+    }
+
+    /// Evaluates the given path (see [`Message::query()`]) against _every_ segment with a matching identifier, returning one value per segment in document order.
+    /// Where [`Message::query()`] only looks at the first matching segment, this is useful for repeating segments (eg multiple OBX's).
+    ///
+    /// A segment identifier of `*` matches every segment in the message (eg `*.F1` returns field 1 of each segment).  This wildcard is only supported here, not by [`Message::query()`].
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::Message;
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let m = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3\rOBX|1|NM|GLU||182\rOBX|2|NM|NA||140")?;
+    /// assert_eq!(m.query_all_segments("OBX.F5"), vec!["182", "140"]);
+    /// assert_eq!(m.query_all_segments("*.F0"), vec!["MSH", "OBX", "OBX"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query_all_segments<'b, S>(&self, idx: S) -> Vec<&'a str>
+    where
+        S: Into<&'b str>,
+    {
+        let idx = Self::trim_query_path(idx.into());
+
+        if !Self::is_valid_query_path(&idx) {
+            return Vec::new();
+        }
+
+        let indices = Self::parse_query_string(&idx);
+        let seg_name = indices[0];
+
+        self.segments
+            .iter()
+            .filter(|s| seg_name == "*" || s.identifier() == seg_name)
+            .map(|s| {
+                if indices.len() < 2 {
+                    s.source
+                } else {
+                    s.query_parts(&indices[1..])
+                }
+            })
+            .collect()
+    }
+
+    /// Queries for the value at the given path (see [`Message::query()`]) and then decodes any escape sequences in the result
+    /// using this message's separators (see [`EscapeSequence`]).  This only allocates if the value contains escape sequences.
+    ///
+    /// Decoding is cheap for values without escape sequences (the common case), as the decoder returns the borrowed value after a single scan for the escape char.
+    /// Messages using the default separators share a single, lazily built decoder (see [`EscapeSequence::default()`]) so there's no per-call set up cost,
+    /// while messages with custom separators build a decoder per call; if you're decoding many values from such a message consider an [`crate::EscaperCache`] instead.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::Message;
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let m = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|1|Joes Obs \\T\\ Gynae")?;
+    /// assert_eq!(m.query_decoded("OBR.F2"), "Joes Obs & Gynae");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query_decoded<'b, S>(&self, idx: S) -> Cow<'a, str>
+    where
+        S: Into<&'b str>,
+    {
+        let value = self.query(idx);
+        if self.separators == Separators::DEFAULT {
+            EscapeSequence::default().decode(value)
+        } else {
+            EscapeSequence::new(self.separators).decode(value)
+        }
+    }
+
+    /// Compares this message to another by their decoded values rather than their raw source, so a value that's escaped in one message
+    /// but not the other (or escaped differently) doesn't prevent a match.  The structure must still match down to the subcomponent level, so an escaped
+    /// separator (eg `a\T\b`) doesn't equal a real split (`a&b`).  Trailing empty fields, repeats, components and subcomponents are ignored,
+    /// so `OBR|1^` equals `OBR|1||`.  MSH-2 (the encoding chars) is compared verbatim, and both messages are decoded with the given escaper.
+    ///
+    /// Values that can't be decoded (eg invalid `\X..\` hex) are compared raw, rather than panicking.  This is mostly useful for regression testing message generators.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::{EscapeSequence, Message, Separators};
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let escaped = Message::try_from("MSH|^~\\&|GHH LAB\rOBR|1|Joes Obs \\T\\ Gynae")?;
+    /// let hex = Message::try_from("MSH|^~\\&|GHH LAB\rOBR|1|Joes Obs \\X26\\ Gynae||")?;
+    /// let escaper = EscapeSequence::new(Separators::default());
+    /// assert!(escaped.semantic_eq(&hex, &escaper));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn semantic_eq(&self, other: &Message, escaper: &EscapeSequence) -> bool {
+        self.segments.len() == other.segments.len()
+            && self
+                .segments
+                .iter()
+                .zip(&other.segments)
+                .all(|(a, b)| Self::segments_semantically_eq(a, b, escaper))
+    }
+
+    /// Compares two segments field by field for [`Message::semantic_eq()`]
+    fn segments_semantically_eq(a: &Segment, b: &Segment, escaper: &EscapeSequence) -> bool {
+        let a_fields = trim_trailing_empty(&a.fields, |f| f.source.is_empty());
+        let b_fields = trim_trailing_empty(&b.fields, |f| f.source.is_empty());
+
+        a_fields.len() == b_fields.len()
+            && a_fields
+                .iter()
+                .zip(b_fields)
+                .enumerate()
+                .all(|(idx, (fa, fb))| {
+                    // MSH-2 (at index 1) holds the separators themselves, and isn't decodable
+                    if idx == 1 && a.is_msh() {
+                        fa.source == fb.source
+                    } else {
+                        Self::fields_semantically_eq(fa, fb, escaper)
+                    }
+                })
+    }
+
+    /// Compares two fields structurally (repeat, component and subcomponent), only decoding the leaf values, for [`Message::semantic_eq()`]
+    fn fields_semantically_eq(a: &Field, b: &Field, escaper: &EscapeSequence) -> bool {
+        let is_empty_component = |c: &Vec<&str>| c.iter().all(|s| s.is_empty());
+        let is_empty_repeat = |r: &Vec<Vec<&str>>| r.iter().all(is_empty_component);
+        let a_repeats = trim_trailing_empty(&a.subcomponents, is_empty_repeat);
+        let b_repeats = trim_trailing_empty(&b.subcomponents, is_empty_repeat);
+
+        let decode = |value: &str| {
+            escaper
+                .decode_checked(value)
+                .map(|decoded| decoded.into_owned())
+                .unwrap_or_else(|_| value.to_string())
+        };
+
+        a_repeats.len() == b_repeats.len()
+            && a_repeats.iter().zip(b_repeats).all(|(ra, rb)| {
+                let a_components = trim_trailing_empty(ra, is_empty_component);
+                let b_components = trim_trailing_empty(rb, is_empty_component);
+
+                a_components.len() == b_components.len()
+                    && a_components.iter().zip(b_components).all(|(ca, cb)| {
+                        let a_subcomponents = trim_trailing_empty(ca, |s| s.is_empty());
+                        let b_subcomponents = trim_trailing_empty(cb, |s| s.is_empty());
+
+                        a_subcomponents.len() == b_subcomponents.len()
+                            && a_subcomponents
+                                .iter()
+                                .zip(b_subcomponents)
+                                .all(|(sa, sb)| sa == sb || decode(sa) == decode(sb))
+                    })
+            })
+    }
+
+    /// Hashes a normalised form of this message, so messages that differ only cosmetically hash equally, which is useful for de-duplication.
+    /// The normalised form ignores the separator chars in use (hashing the structure rather than the raw source), as well as trailing empty
+    /// fields, repeats, components and subcomponents, so `OBR|1^|` hashes the same as `OBR|1`.  Escape sequences are hashed as sent.
+    ///
+    /// This uses the standard library's `DefaultHasher`, so the value is stable for a given build but may change between Rust versions, and shouldn't be persisted long term.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::Message;
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let a = Message::try_from("MSH|^~\\&|GHH LAB\rPID|||555-44-4444")?;
+    /// let b = Message::try_from("MSH*^~\\&*GHH LAB\rPID***555-44-4444***")?;
+    /// assert_eq!(a.content_hash(), b.content_hash());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+
+        for segment in &self.segments {
+            let fields = trim_trailing_empty(&segment.fields, |f| f.source.is_empty());
+            fields.len().hash(&mut hasher);
+
+            for (idx, field) in fields.iter().enumerate() {
+                // MSH-2 is just the (already normalised away) separators
+                if idx == 1 && segment.is_msh() {
+                    continue;
+                }
+
+                let is_empty_component = |c: &Vec<&str>| c.iter().all(|s| s.is_empty());
+                let repeats =
+                    trim_trailing_empty(&field.subcomponents, |r| r.iter().all(is_empty_component));
+                repeats.len().hash(&mut hasher);
+
+                for repeat in repeats {
+                    let components = trim_trailing_empty(repeat, is_empty_component);
+                    components.len().hash(&mut hasher);
+
+                    for component in components {
+                        trim_trailing_empty(component, |s| s.is_empty()).hash(&mut hasher);
+                    }
+                }
+            }
+        }
+
+        hasher.finish()
+    }
+
+    /// Strips incidental whitespace from around each part of a query path (eg `" PID .F5"`), and any trailing `.` chars (eg `"PID.F5."`),
+    /// only allocating if there's whitespace to strip.
+    fn trim_query_path(query: &str) -> Cow<'_, str> {
+        let query = query.trim_end_matches(|c: char| c == '.' || c.is_whitespace());
+        if query.contains(char::is_whitespace) {
+            let parts: Vec<&str> = query.split('.').map(str::trim).collect();
+            Cow::Owned(parts.join("."))
+        } else {
+            Cow::Borrowed(query)
+        }
+    }
+
+    /// Checks the query path conforms to the grammar documented on [`Message::query()`]
+    fn is_valid_query_path(query: &str) -> bool {
+        let mut parts = query.split('.');
+        match parts.next() {
+            Some(seg_name) if !seg_name.is_empty() => {}
+            _ => return false,
+        }
+
+        let mut seen = Vec::with_capacity(4);
+        parts.all(|part| {
+            let mut chars = part.chars();
+            let prefix = match chars.next() {
+                Some(c) => c.to_ascii_uppercase(),
+                None => return false,
+            };
+            let digits = chars.as_str();
+
+            matches!(prefix, 'F' | 'R' | 'C' | 'S')
+                && !seen.contains(&prefix)
+                && !digits.is_empty()
+                && digits.chars().all(|c| c.is_ascii_digit())
+                && {
+                    seen.push(prefix);
+                    true
+                }
+        })
+    }
+
+    /// Parse query/index string to fill-in missing values.
+    /// Required when conumer requests "PID.F3.C1" to pass integers down
+    /// to the usize indexers at the appropriate positions
+    fn parse_query_string(query: &str) -> Vec<&str> {
+        fn query_idx_pos(indices: &[&str], idx: &str) -> Option<usize> {
+            indices[1..]
+                .iter()
+                .position(|r| r[0..1].to_uppercase() == idx)
+        }
+        let indices: Vec<&str> = query.split('.').collect();
+        // Leave segment name untouched - complex match
+        let mut res = vec![indices[0]];
+        // Get segment positions, if any
+        let sub_pos = query_idx_pos(&indices, "S");
+        let com_pos = query_idx_pos(&indices, "C");
+        let rep_pos = query_idx_pos(&indices, "R");
+        let fld_pos = query_idx_pos(&indices, "F");
+        // Push segment values to result, returning early if possible
+        match fld_pos {
+            Some(f) => res.push(indices[f + 1]),
+            None => {
+                // If empty but we have subsections, default to F1
+                if rep_pos.is_some() || com_pos.is_some() || sub_pos.is_some() {
+                    res.push("F1")
+                } else {
+                    return res;
+                }
+            }
+        };
+        match rep_pos {
+            Some(r) => res.push(indices[r + 1]),
+            None => {
+                // If empty but we have subsections, default to R1
+                if com_pos.is_some() || sub_pos.is_some() {
+                    res.push("R1")
+                } else {
+                    return res;
+                }
+            }
+        };
+        match com_pos {
+            Some(c) => res.push(indices[c + 1]),
+            None => {
+                // If empty but we have a subcomponent, default to C1
+                if sub_pos.is_some() {
+                    res.push("C1")
+                } else {
+                    return res;
+                }
+            }
+        };
+        if let Some(s) = sub_pos {
+            res.push(indices[s + 1])
+        }
+        res
+    }
+}
+
+/// Appends a single line of [`Message::debug_tree()`] output, eg `    C2: value`
+fn push_tree_line(tree: &mut String, depth: usize, prefix: char, index: usize, value: &str) {
+    for _ in 0..depth {
+        tree.push_str("  ");
+    }
+    tree.push(prefix);
+    tree.push_str(&index.to_string());
+    tree.push(':');
+    if !value.is_empty() {
+        tree.push(' ');
+        tree.push_str(value);
+    }
+    tree.push('\n');
+}
+
+/// Returns the given slice without any trailing items matching `is_empty`
+fn trim_trailing_empty<T>(items: &[T], is_empty: impl Fn(&T) -> bool) -> &[T] {
+    let len = items
+        .iter()
+        .rposition(|i| !is_empty(i))
+        .map_or(0, |i| i + 1);
+    &items[..len]
+}
+
+impl<'a> TryFrom<&'a str> for Message<'a> {
+    type Error = Hl7ParseError;
+
+    /// Takes the source HL7 string and parses it into a message.  Segments
+    /// and other data are slices (`&str`) into the source HL7 for minimal (preferably 0) copying.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::Message;
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let m = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn try_from(source: &'a str) -> Result<Self, Self::Error> {
+        let separators = str::parse::<Separators>(source)?;
+
+        // Some systems pad messages with trailing NULs (or other control chars), which would otherwise turn up as a garbage final segment.
+        // We only ignore them for parsing, the source slice is kept intact.
+        let content = source.trim_end_matches(|c: char| c.is_control() && c != '\r' && c != '\n');
+
+        // The spec mandates `\r` between segments, but we tolerate `\n` and `\r\n` as well
+        let line_ending = LineEnding::detect(content);
+
+        // A terminator after the final segment is common, and shouldn't be treated as an (empty) extra segment
+        let content = content
+            .strip_suffix(line_ending.as_str())
+            .unwrap_or(content);
+
+        let possible = content
+            .split(line_ending.as_str())
+            .map(|line| Segment::parse(line, &separators));
+
+        let segments: Vec<Segment> = possible.collect::<Result<Vec<Segment>, Self::Error>>()?;
+
+        let m = Message {
+            source,
+            segments,
+            separators,
+            line_ending,
+        };
+
+        Ok(m)
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for Message<'a> {
+    type Error = Hl7ParseError;
+
+    /// Takes the source HL7 bytes and parses them into a message, without copying.
+    /// The bytes are validated as UTF-8 once, returning an `Err(Hl7ParseError::Generic)` if invalid rather than panicking.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::Message;
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let bytes = b"MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4";
+    /// let m = Message::try_from(&bytes[..])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn try_from(source: &'a [u8]) -> Result<Self, Self::Error> {
+        let source = std::str::from_utf8(source)
+            .map_err(|e| Hl7ParseError::Generic(format!("Message is not valid UTF-8: {}", e)))?;
+        Message::try_from(source)
+    }
+}
+
+impl<'a> Display for Message<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl<'a> AsRef<str> for Message<'a> {
+    /// Returns the source string for this message, allowing it to be passed directly to APIs taking `impl AsRef<str>`
+    fn as_ref(&self) -> &str {
+        self.source
+    }
+}
+
+impl<'a> From<&Message<'a>> for String {
+    /// Serialises the message back to HL7 text, copying the source string.
+    fn from(message: &Message<'a>) -> String {
+        String::from(message.source)
+    }
+}
+
+impl<'a> From<Message<'a>> for String {
+    /// Serialises the message back to HL7 text, copying the source string.
+    fn from(message: Message<'a>) -> String {
+        String::from(message.source)
+    }
+}
+
+impl<'a> Clone for Message<'a> {
+    /// Creates a new cloned Message object referencing the same source slice as the original.
+    /// The already parsed segments are cloned rather than re-parsing the source, so messages from [`Message::parse_lenient()`] or [`Message::from_parts()`] clone safely.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::Message;
+    /// # use std::convert::TryFrom;
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let m = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4")?;
+    /// let cloned = m.clone(); // this object is looking at the same string slice as m
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn clone(&self) -> Self {
+        Message {
+            source: self.source,
+            segments: self.segments.clone(),
+            separators: self.separators,
+            line_ending: self.line_ending,
+        }
+    }
+}
+
+impl<'a> Index<usize> for Message<'a> {
+    type Output = &'a str;
+
+    /// Access Segment string reference by numeric index
+    fn index(&self, idx: usize) -> &Self::Output {
+        if idx >= self.segments.len() {
+            return &"";
+        }
+        &self.segments[idx].source
+    }
+}
+#[cfg(feature = "string_index")]
+impl<'a> Index<String> for Message<'a> {
+    type Output = &'a str;
+
+    /// Access Segment, Field, or sub-field string references by string index
+    #[cfg(feature = "string_index")]
+    fn index(&self, idx: String) -> &Self::Output {
+        let idx = Self::trim_query_path(&idx);
+        if !Self::is_valid_query_path(&idx) {
+            return &"";
+        }
+
+        // Parse index elements
+        let indices = Self::parse_query_string(&idx);
+        let seg_name = indices[0];
+        let seg = match self.segments.iter().find(|s| s.identifier() == seg_name) {
+            Some(seg) => seg,
+            None => return &"",
+        };
+        if indices.len() < 2 {
+            &seg.source
+        } else {
+            &seg[indices[1..].join(".")]
+        }
+    }
+}
+
+#[cfg(feature = "string_index")]
+impl<'a> Index<&str> for Message<'a> {
+    type Output = &'a str;
+
+    #[cfg(feature = "string_index")]
+    fn index(&self, idx: &str) -> &Self::Output {
+        &self[String::from(idx)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ensure_segments_are_returned() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|segment";
+        let msg = Message::try_from(hl7)?;
+
+        assert_eq!(msg.segments.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    #[allow(deprecated)] // `segments_by_identifier()` must keep working until it's removed
+    fn ensure_missing_segments_are_not_found() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|segment";
+        let msg = Message::try_from(hl7)?;
         assert_eq!(msg.segments_by_identifier("EVN").unwrap().len(), 0);
         Ok(())
     }
 
     #[test]
-    fn ensure_segments_convert_to_vectors() -> Result<(), Hl7ParseError> {
+    fn ensure_segments_named_finds_matching_segments() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|1\rOBX|1\rOBR|2";
+        let msg = Message::try_from(hl7)?;
+
+        let obrs = msg.segments_named("OBR");
+        assert_eq!(obrs.len(), 2);
+        assert_eq!(obrs[1].as_str(), "OBR|2");
+        assert!(msg.segments_named("EVN").is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_segments_convert_to_vectors() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|segment";
+        let msg = Message::try_from(hl7)?;
+        let segs = msg.segments_named("OBR");
+        let sval = segs.first().unwrap().fields.first().unwrap().as_str();
+        let vecs = Message::segments_to_str_vecs(segs).unwrap();
+        let vval = vecs.first().unwrap().first().unwrap();
+
+        assert_eq!(vval, &sval);
+        Ok(())
+    }
+    #[test]
+    fn ensure_clones_are_owned() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|segment";
+        let msg = Message::try_from(hl7)?;
+        // Verify that we can clone and take ownership
+        let dolly = msg.clone();
+        let dolly = dolly.to_owned();
+        assert_eq!(msg.query("MSH.F7"), dolly.query("MSH.F7"));
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_message_from_bytes() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|segment";
+        let msg = Message::try_from(hl7.as_bytes())?;
+        assert_eq!(msg.as_str(), hl7);
+
+        let invalid: &[u8] = b"MSH|^~\\&|GHH LAB|\xFF\xFE";
+        assert!(Message::try_from(invalid).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_to_string() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|segment";
+        let msg = Message::try_from(hl7)?;
+        assert_eq!(msg.to_string(), String::from(hl7));
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_string_round_trip() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|segment";
+        let msg = Message::try_from(hl7)?;
+
+        let from_ref = String::from(&msg);
+        let reparsed = Message::try_from(from_ref.as_str())?;
+        assert_eq!(msg, reparsed);
+
+        let from_owned: String = msg.into();
+        assert_eq!(from_owned, hl7);
+        Ok(())
+    }
+
+    #[test]
+    #[allow(deprecated)] // `new()` must keep working until it's removed
+    fn ensure_message_creation() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|segment";
+        let msg0 = Message::try_from(hl7)?;
+        let msg1 = Message::new(hl7);
+
+        assert_eq!(msg0, msg1);
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_query() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|segment^sub&segment";
+        let msg = Message::try_from(hl7)?;
+        assert_eq!(msg.query("OBR.F1.R1.C2"), "sub&segment");
+        assert_eq!(msg.query(&*"OBR.F1.R1.C1".to_string()), "segment"); // Test the Into param with a String
+        assert_eq!(msg.query(&*String::from("OBR.F1.R1.C1")), "segment");
+        assert_eq!(msg.query("MSH.F1"), "|");
+        assert_eq!(msg.query("MSH.F2"), "^~\\&");
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_parts_round_trip() -> Result<(), Hl7ParseError> {
         let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|segment";
         let msg = Message::try_from(hl7)?;
-        let segs = msg.segments_by_identifier("OBR")?;
-        let sval = segs.first().unwrap().fields.first().unwrap().as_str();
-        let vecs = Message::segments_to_str_vecs(segs).unwrap();
-        let vval = vecs.first().unwrap().first().unwrap();
+        let (source, segments, separators) = msg.clone().into_parts();
+        let rebuilt = Message::from_parts(source, segments, separators)?;
 
-        assert_eq!(vval, &sval);
+        assert_eq!(msg, rebuilt);
         Ok(())
     }
+
     #[test]
-    fn ensure_clones_are_owned() -> Result<(), Hl7ParseError> {
+    fn ensure_from_parts_requires_msh() -> Result<(), Hl7ParseError> {
         let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|segment";
+        let (source, mut segments, separators) = Message::try_from(hl7)?.into_parts();
+        assert!(matches!(
+            Message::from_parts("", segments.clone(), separators),
+            Err(Hl7ParseError::Generic(_))
+        ));
+
+        segments.remove(0);
+        assert!(matches!(
+            Message::from_parts(source, segments, separators),
+            Err(Hl7ParseError::Msh1Msh2(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_segment_names_are_ordered() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rPID|||555-44-4444\rOBR|1|845439^GHH OE\rOBX|1|SN|1554-5^GLUCOSE";
         let msg = Message::try_from(hl7)?;
-        // Verify that we can clone and take ownership
-        let dolly = msg.clone();
-        let dolly = dolly.to_owned();
-        assert_eq!(msg.query("MSH.F7"), dolly.query("MSH.F7"));
+        assert_eq!(msg.segment_names(), vec!["MSH", "PID", "OBR", "OBX"]);
         Ok(())
     }
 
     #[test]
-    fn ensure_to_string() -> Result<(), Hl7ParseError> {
+    fn ensure_walk_visits_all_components() -> Result<(), Hl7ParseError> {
+        struct ComponentCounter(usize);
+
+        impl<'a> Visitor<'a> for ComponentCounter {
+            fn visit_component(&mut self, _component: &'a str) {
+                self.0 += 1;
+            }
+        }
+
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rPID|||555-44-4444||EVERYWOMAN^EVE^E^^^^L|JONES|19620320|F|||153 FERNWOOD DR.^^STATESVILLE^OH^35292\rOBX|1|SN|1554-5^GLUCOSE^POST 12H CFST:MCNC:PT:SER/PLAS:QN||^182|mg/dl|70_105|H|||F";
+        let msg = Message::try_from(hl7)?;
+
+        let expected: usize = msg
+            .segments
+            .iter()
+            .flat_map(|s| s.fields.iter().skip(1))
+            .map(|f| f.components.iter().map(|c| c.len()).sum::<usize>())
+            .sum();
+
+        let mut counter = ComponentCounter(0);
+        msg.walk(&mut counter);
+
+        assert_eq!(counter.0, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_separator_usage_is_detected() -> Result<(), Hl7ParseError> {
+        let plain = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|segment")?;
+        assert!(!plain.uses_repeats());
+        assert!(!plain.uses_escapes());
+
+        let repeats = Message::try_from(
+            "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|a~b",
+        )?;
+        assert!(repeats.uses_repeats());
+        assert!(!repeats.uses_escapes());
+
+        let escapes = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|a\\T\\b")?;
+        assert!(!escapes.uses_repeats());
+        assert!(escapes.uses_escapes());
+
+        // a source too short to hold the header has nothing after the encoding chars
+        let seps = Separators::default();
+        let short = Message::from_parts("MSH", vec![Segment::parse("MSH", &seps)?], seps)?;
+        assert!(!short.uses_repeats());
+        assert!(!short.uses_escapes());
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_ack_detection() -> Result<(), Hl7ParseError> {
+        let ack = Message::try_from("MSH|^~\\&|CATH|StJohn|AcmeHIS|StJohn|20061019172719||ACK^O01|MSGID12349876|P|2.3\rMSA|AA|MSGID12349876")?;
+        assert!(ack.is_ack());
+        assert!(!ack.is_nack());
+        assert_eq!(ack.ack_code(), Some("AA"));
+
+        let nack = Message::try_from("MSH|^~\\&|CATH|StJohn|AcmeHIS|StJohn|20061019172719||ACK^O01|MSGID12349876|P|2.3\rMSA|AE|MSGID12349876")?;
+        assert!(nack.is_nack());
+
+        let oru = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|segment")?;
+        assert!(!oru.is_ack());
+        assert!(!oru.is_nack());
+        assert_eq!(oru.ack_code(), None);
+
+        let mut empty = ack.clone();
+        empty.segments.clear();
+        assert!(!empty.is_ack());
+        assert!(!empty.is_nack());
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_retain_segments() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rPID|||555-44-4444\rOBR|1|845439\rOBX|1|SN|1554-5\rNTE|1||Comment\rOBX|2|SN|1554-6";
+        let msg = Message::try_from(hl7)?;
+
+        let reduced = msg.retain_segments(|s| s.identifier() == "OBX");
+        assert_eq!(reduced, "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBX|1|SN|1554-5\rOBX|2|SN|1554-6");
+
+        let reduced = Message::try_from(reduced.as_str())?;
+        assert_eq!(reduced.segment_names(), vec!["MSH", "OBX", "OBX"]);
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_line_ending_is_detected_and_round_trips() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\r\nOBR|segment\r\nNTE|1||Comment";
+        let msg = Message::try_from(hl7)?;
+
+        assert_eq!(msg.detected_line_ending(), LineEnding::CrLf);
+        assert_eq!(msg.segment_names(), vec!["MSH", "OBR", "NTE"]);
+        assert_eq!(msg.to_string(), hl7);
+        assert_eq!(
+            msg.retain_segments(|s| s.identifier() == "OBR"),
+            "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\r\nOBR|segment"
+        );
+
+        let lf = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3\nOBR|segment")?;
+        assert_eq!(lf.detected_line_ending(), LineEnding::Lf);
+        assert_eq!(lf.query("OBR.F1"), "segment");
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_debug_tree() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|segment^sub&segment~second";
+        let msg = Message::try_from(hl7)?;
+        let tree = msg.debug_tree();
+
+        let expected = "OBR
+  F1: segment^sub&segment~second
+    R1: segment^sub&segment
+      C1: segment
+      C2: sub&segment
+        S1: sub
+        S2: segment
+    R2: second
+";
+        assert!(tree.starts_with("MSH\n  F2: ^~\\&\n  F3: GHH LAB\n"));
+        assert!(tree.contains("  F9: ORU^R01\n    C1: ORU\n    C2: R01\n"));
+        assert!(tree.ends_with(expected));
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_query_all_segments() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|1\rOBX|1|NM|GLU||182|mg/dl\rOBX|2|NM|NA||140|mmol/L\rOBX|3|NM|K||4.1|mmol/L";
+        let msg = Message::try_from(hl7)?;
+
+        assert_eq!(msg.query_all_segments("OBX.F5"), vec!["182", "140", "4.1"]);
+        assert_eq!(
+            msg.query_all_segments("OBX.F6.C1"),
+            vec!["mg/dl", "mmol/L", "mmol/L"]
+        );
+        assert_eq!(msg.query_all_segments("OBR"), vec!["OBR|1"]);
+        assert!(msg.query_all_segments("NTE.F3").is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_query_numeric() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rPID|||555-44-4444||EVERYWOMAN^EVE^E^^^^L|JONES|19620320|F|||153 FERNWOOD DR.^^STATESVILLE^OH^35292&AU";
+        let msg = Message::try_from(hl7)?;
+
+        assert_eq!(msg.query_numeric("PID.5.1"), "EVERYWOMAN");
+        assert_eq!(msg.query_numeric("PID.5.2"), "EVE");
+        assert_eq!(msg.query_numeric("PID.5"), "EVERYWOMAN^EVE^E^^^^L");
+        assert_eq!(msg.query_numeric("PID.11.5.2"), "AU");
+        // the prefixed grammar agrees
+        assert_eq!(msg.query_numeric("PID.5.1"), msg.query("PID.F5.C1"));
+        // malformed or missing
+        assert_eq!(msg.query_numeric("PID"), "");
+        assert_eq!(msg.query_numeric("PID.F5"), "");
+        assert_eq!(msg.query_numeric("PID.5.0"), "");
+        assert_eq!(msg.query_numeric("PID.99"), "");
+        assert_eq!(msg.query_numeric("OBX.5"), "");
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_concatenate_text() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBX|1|TX\rNTE|1||Patient fasting\rNTE|2||since midnight\rOBX|2|TX\rNTE|1||Unrelated note";
+        let msg = Message::try_from(hl7)?;
+
+        assert_eq!(
+            msg.concatenate_text("NTE", 3, "\n"),
+            "Patient fasting\nsince midnight"
+        );
+        assert_eq!(msg.concatenate_text("ZZZ", 3, "\n"), "");
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_query_ignores_whitespace_in_path() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rPID|||555-44-4444||EVERYWOMAN^EVE^E^^^^L";
+        let msg = Message::try_from(hl7)?;
+
+        assert_eq!(msg.query(" PID .F5"), "EVERYWOMAN^EVE^E^^^^L");
+        assert_eq!(msg.query("PID.F5.C1 "), "EVERYWOMAN");
+        assert_eq!(msg.query("PID . F5 . C2"), "EVE");
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_lenient_parse_reports_issues() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\r\rPID|||555-44-4444\rOBR|1\r\0\0";
+        let (msg, issues) = Message::parse_lenient(hl7)?;
+
+        assert_eq!(msg.segment_names(), vec!["MSH", "PID", "OBR"]);
+        assert_eq!(msg.query("PID.F3"), "555-44-4444");
+        assert_eq!(
+            issues,
+            vec![
+                ValidationIssue::MissingEncodingChars,
+                ValidationIssue::TrailingControlChars,
+                ValidationIssue::EmptySegment(1)
+            ]
+        );
+        assert!(Message::try_from(hl7).is_err());
+
+        // cloning mustn't re-parse the source, which `try_from()` rejects
+        let cloned = msg.clone();
+        assert_eq!(cloned, msg);
+        assert_eq!(cloned.query("PID.F3"), "555-44-4444");
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_lenient_parse_of_clean_message_has_no_issues() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rPID|||555-44-4444\r";
+        let (msg, issues) = Message::parse_lenient(hl7)?;
+
+        assert_eq!(msg.segment_names(), vec!["MSH", "PID"]);
+        assert!(issues.is_empty());
+        assert!(Message::parse_lenient("PID|||555-44-4444").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_as_ref_str() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rPID|||555-44-4444";
+        let msg = Message::try_from(hl7)?;
+
+        assert_eq!(AsRef::<str>::as_ref(&msg), hl7);
+        assert_eq!(msg.segments[1].as_ref(), "PID|||555-44-4444");
+        assert_eq!(msg.segments[1].fields[3].as_ref(), "555-44-4444");
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_try_query_distinguishes_empty_from_absent() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBX|1|a^^c";
+        let msg = Message::try_from(hl7)?;
+
+        assert_eq!(msg.try_query("OBX.F2.C2"), Some(""));
+        assert_eq!(msg.try_query("OBX.F2.C9"), None);
+        assert_eq!(msg.try_query("OBX.F2.C3"), Some("c"));
+        assert_eq!(msg.try_query("OBX.F9"), None);
+        assert_eq!(msg.try_query("OBX"), Some("OBX|1|a^^c"));
+        assert_eq!(msg.try_query("PID.F1"), None);
+        assert_eq!(msg.try_query("OBX.2"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_lazy_segments_match_full_parse() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\r\nPID|||555-44-4444\r\nOBX|1|SN|1554-5^GLUCOSE||^182\r\nOBX|2|SN|1554-5^GLUCOSE||^190\0";
+        let msg = Message::try_from(hl7)?;
+
+        let lazy = Message::iter_raw_segments(hl7).collect::<Result<Vec<Segment>, _>>()?;
+        assert_eq!(lazy, msg.segments);
+
+        let mut failed = Message::iter_raw_segments("PID|||555-44-4444");
+        assert!(matches!(
+            failed.next(),
+            Some(Err(Hl7ParseError::Msh1Msh2(_)))
+        ));
+        assert!(failed.next().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_subcomponent_query_from_message() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rPID|||555-44-4444||EVERYWOMAN^EVE^E^^^^L|JONES|19620320|F|||153 FERNWOOD DR.^^STATESVILLE^OH^35292&AU&ISO~PO BOX 1^^STATESVILLE^OH^35293&AU";
+        let msg = Message::try_from(hl7)?;
+
+        assert_eq!(msg.query("PID.F11.R1.C5.S1"), "35292");
+        assert_eq!(msg.query("PID.F11.R1.C5.S3"), "ISO");
+        assert_eq!(msg.query("PID.F11.R2.C5.S2"), "AU");
+        // missing R and C default to 1
+        assert_eq!(msg.query("PID.F5.S1"), "EVERYWOMAN");
+        assert_eq!(msg.query("PID.F11.C5.S2"), "AU");
+        // out of range
+        assert_eq!(msg.query("PID.F11.R1.C5.S4"), "");
+        assert_eq!(msg.query("PID.F11.R2.C5.S3"), "");
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_wildcard_matches_every_segment() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rPID|||555-44-4444\rOBR|1|845439^GHH OE\rOBX|1|SN|1554-5^GLUCOSE";
+        let msg = Message::try_from(hl7)?;
+
+        assert_eq!(msg.query_all_segments("*.F1"), vec!["|", "", "1", "1"]);
+        assert_eq!(msg.query_all_segments("*").len(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_encoding_characters_are_raw() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rPID|||555-44-4444";
+        let msg = Message::try_from(hl7)?;
+        assert_eq!(msg.encoding_characters(), "^~\\&");
+
+        // v2.7+ may add a truncation char, which isn't in `Separators`
+        let hl7 = "MSH|^~\\&#|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.7";
+        let msg = Message::try_from(hl7)?;
+        assert_eq!(msg.encoding_characters(), "^~\\&#");
+        assert_eq!(msg.get_separators().to_string(), "^~\\&");
+
+        let mut empty = msg.clone();
+        empty.segments.clear();
+        assert_eq!(empty.encoding_characters(), "");
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_embedded_empty_line_is_an_error() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\r\rPID|||555-44-4444";
+        assert!(matches!(
+            Message::try_from(hl7),
+            Err(Hl7ParseError::Generic(_))
+        ));
+
+        // a single trailing terminator is fine
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rPID|||555-44-4444\r";
+        let msg = Message::try_from(hl7)?;
+        assert_eq!(msg.segment_names(), vec!["MSH", "PID"]);
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_numeric_index_boundary() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rPID|||555-44-4444";
+        let msg = Message::try_from(hl7)?;
+
+        assert_eq!(msg[1], "PID|||555-44-4444");
+        assert_eq!(msg[2], "");
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_validated_query_reports_errors() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rPID|||555-44-4444||EVERYWOMAN^EVE^E^^^^L";
+        let msg = Message::try_from(hl7)?;
+
+        assert_eq!(msg.try_query_validated("PID.F5.C2")?, "EVE");
+        assert_eq!(msg.try_query_validated("PID.F50")?, "");
+        assert_eq!(msg.try_query_validated("OBX.F5")?, "");
+        assert!(matches!(
+            msg.try_query_validated("PID.X3"),
+            Err(Hl7ParseError::InvalidQuery(_))
+        ));
+        assert!(msg.try_query_validated("PID.C1.F5").is_err());
+        // the lenient query still accepts out of order paths
+        assert_eq!(msg.query("PID.C1.F5"), "EVERYWOMAN");
+
+        // F0 is the segment identifier in both
+        assert_eq!(msg.try_query_validated("PID.F0")?, "PID");
+        assert_eq!(msg.query("PID.F0"), "PID");
+        assert_eq!(msg.try_query_validated("MSH.F0")?, "MSH");
+        assert_eq!(msg.query("MSH.F0"), "MSH");
+        // but other parts are still numbered from 1
+        assert!(msg.try_query_validated("PID.F5.C0").is_err());
+        assert_eq!(msg.query("PID.F5.C0"), "");
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_field_is_resolved() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rPID|||555-44-4444||EVERYWOMAN^EVE^E^^^^L~SMITH^EVE";
+        let msg = Message::try_from(hl7)?;
+
+        let name = msg.field("PID.F5").unwrap();
+        assert_eq!(name.repeats.len(), 2);
+        assert_eq!(name.first_component(), "EVERYWOMAN");
+        assert_eq!(name[(1, 0)], "SMITH");
+        assert_eq!(name.source, msg.query("PID.F5"));
+
+        assert!(msg.field("PID.F50").is_none());
+        assert!(msg.field("OBX.F5").is_none());
+        assert!(msg.field("PID").is_none());
+        assert!(msg.field("PID.F5.C1").is_none());
+
+        // F0 is the identifier, as per `query()`
+        assert_eq!(msg.field("PID.F0").unwrap().source, msg.query("PID.F0"));
+        assert_eq!(msg.field("MSH.F0").unwrap().source, "MSH");
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_bare_segment_query_returns_whole_segment() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|1|845439^GHH OE|1045813^GHH LAB\rOBR|2|Second";
+        let msg = Message::try_from(hl7)?;
+
+        assert_eq!(msg.query("OBR"), "OBR|1|845439^GHH OE|1045813^GHH LAB");
+        assert_eq!(msg.query("EVN"), "");
+        assert_eq!(msg.query("EVN.F1"), "");
+        assert_eq!(msg.query("MSHX"), "");
+        // segments are matched by their full identifier, not a prefix
+        assert_eq!(msg.query("OB"), "");
+        assert_eq!(msg.query("OB.F2"), "");
+        assert_eq!(msg.try_query("OB.F2"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_msh_query_uses_spec_numbering() -> Result<(), Hl7ParseError> {
         let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|segment";
         let msg = Message::try_from(hl7)?;
-        assert_eq!(msg.to_string(), String::from(hl7));
+
+        assert_eq!(msg.query("MSH.F9.C1"), "ORU");
+        assert_eq!(msg.query("MSH.F9.C2"), "R01");
+        assert_eq!(msg.query("MSH.F10"), "CNTRL-3456");
+        assert_eq!(msg.get_field("MSH", 9), Some("ORU^R01"));
+        assert_eq!(msg.get_field("MSH", 1), Some("|"));
         Ok(())
     }
 
     #[test]
-    fn ensure_message_creation() -> Result<(), Hl7ParseError> {
+    fn ensure_trailing_dot_in_query_is_ignored() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rPID|||555-44-4444||EVERYWOMAN^EVE";
+        let msg = Message::try_from(hl7)?;
+
+        assert_eq!(msg.query("PID.F5."), msg.query("PID.F5"));
+        assert_eq!(msg.query("PID.F5.C2. "), "EVE");
+        assert_eq!(msg.try_query("PID.F5."), Some("EVERYWOMAN^EVE"));
+        assert_eq!(msg.query_all_segments("PID.F3."), vec!["555-44-4444"]);
+        assert_eq!(msg.query("PID.F5..C2"), ""); // an empty part mid-path is still invalid
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_semantic_eq_compares_decoded_values() -> Result<(), Hl7ParseError> {
+        let escaper = EscapeSequence::new(Separators::default());
+        let escaped =
+            Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3\rOBR|1|Joes Obs \\T\\ Gynae|a^b")?;
+        let hex =
+            Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3\rOBR|1|Joes Obs \\X26\\ Gynae|a^b^^||")?;
+        let different =
+            Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3\rOBR|1|Joes Obs \\F\\ Gynae|a^b")?;
+
+        assert!(escaped.semantic_eq(&hex, &escaper));
+        assert!(hex.semantic_eq(&escaped, &escaper));
+        assert!(!escaped.semantic_eq(&different, &escaper));
+        assert_ne!(escaped.as_str(), hex.as_str());
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_semantic_eq_respects_structure() -> Result<(), Hl7ParseError> {
+        let escaper = EscapeSequence::new(Separators::default());
+        let msg = Message::try_from("MSH|^~\\&|GHH LAB\rOBR|1|a~b")?;
+
+        assert!(msg.semantic_eq(&msg, &escaper));
+        assert!(!msg.semantic_eq(
+            &Message::try_from("MSH|^~\\&|GHH LAB\rOBR|1|a^b")?,
+            &escaper
+        ));
+
+        // a real subcomponent split isn't the same as an escaped subcomponent separator
+        let split = Message::try_from("MSH|^~\\&|GHH LAB\rOBR|1|a&b")?;
+        let escaped = Message::try_from("MSH|^~\\&|GHH LAB\rOBR|1|a\\T\\b")?;
+        assert!(!split.semantic_eq(&escaped, &escaper));
+        assert!(!escaped.semantic_eq(&split, &escaper));
+        assert!(split.semantic_eq(
+            &Message::try_from("MSH|^~\\&|GHH LAB\rOBR|1|a&b&^")?,
+            &escaper
+        ));
+        assert!(!msg.semantic_eq(
+            &Message::try_from("MSH|^~\\&|GHH LAB\rOBR|1|a~b\rOBX|1")?,
+            &escaper
+        ));
+        assert!(!msg.semantic_eq(
+            &Message::try_from("MSH|^~\\&|GHH LAB\rOBX|1|a~b")?,
+            &escaper
+        ));
+        assert!(!msg.semantic_eq(
+            &Message::try_from("MSH|^~\\&|GHH LAB\rOBR|1|a~b|\\XZZ\\")?,
+            &escaper
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_query_many_matches_individual_queries() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|1|first\rPID|||555-44-4444~555-44-5555||EVERYWOMAN^EVE\rOBR|2|second";
+        let msg = Message::try_from(hl7)?;
+        let paths = [
+            "MSH.F9.C1",
+            "OBR.F2",
+            "PID.F3.R2",
+            "PID.F5.C2",
+            "PID",
+            " PID . F5 ",
+            "PID.3",
+            "EVN.F1",
+            "PI.F3",
+            "OB",
+        ];
+
+        let values = msg.query_many(&paths);
+        let expected: Vec<&str> = paths.iter().map(|p| msg.query(*p)).collect();
+        assert_eq!(values, expected);
+        assert_eq!(values[1], "first");
+        assert_eq!(values[8], "");
+        assert_eq!(values[9], "");
+        assert!(msg.query_many(&[]).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_validate_reports_missing_msh_fields() -> Result<(), Hl7ParseError> {
+        let complete = Message::try_from(
+            "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rPID|1",
+        )?;
+        assert!(complete.validate().is_empty());
+
+        let (truncated, issues) = Message::parse_lenient(
+            "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01\rPID|1",
+        )?;
+        assert!(issues.is_empty());
+        assert_eq!(
+            truncated.validate(),
+            vec![
+                ValidationIssue::MissingMshField(10),
+                ValidationIssue::MissingMshField(11),
+                ValidationIssue::MissingMshField(12)
+            ]
+        );
+        assert_eq!(
+            truncated.validate()[1].to_string(),
+            "Required field MSH-11 is missing"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_content_hash_ignores_trailing_empties() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rPID|||555-44-4444||EVERYWOMAN^EVE";
+        let padded = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4|||\rPID|||555-44-4444~||EVERYWOMAN^EVE^^&|||";
+        let different = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rPID|||555-44-4444||EVERYWOMAN^EVA";
+        let moved = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rPID||555-44-4444|||EVERYWOMAN^EVE";
+
+        let hash = Message::try_from(hl7)?.content_hash();
+        assert_eq!(hash, Message::try_from(hl7)?.content_hash());
+        assert_eq!(hash, Message::try_from(padded)?.content_hash());
+        assert_ne!(hash, Message::try_from(different)?.content_hash());
+        assert_ne!(hash, Message::try_from(moved)?.content_hash());
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_observations_are_extracted() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rPID|||555-44-4444||EVERYWOMAN^EVE^E^^^^L|JONES|19620320|F|||153 FERNWOOD DR.^^STATESVILLE^OH^35292||(206)3345232|(206)752-121||||AC555444444||67-A4335^OH^20030520\rOBR|1|845439^GHH OE|1045813^GHH LAB|15545^GLUCOSE|||200202150730|||||||||555-55-5555^PRIMARY^PATRICIA P^^^^MD^^|||||||||F||||||444-44-4444^HIPPOCRATES^HOWARD H^^^^MD\rOBX|1|SN|1554-5^GLUCOSE^POST 12H CFST:MCNC:PT:SER/PLAS:QN||^182|mg/dl|70_105|H|||F";
+        let msg = Message::try_from(hl7)?;
+
+        let observations = msg.observations();
+        assert_eq!(observations.len(), 1);
+
+        let glucose = &observations[0];
+        assert_eq!(glucose.set_id, "1");
+        assert_eq!(glucose.value_type, "SN");
+        assert_eq!(glucose.code.identifier, "1554-5");
+        assert_eq!(glucose.code.text, "GLUCOSE");
+        assert_eq!(glucose.value, "^182");
+        assert_eq!(glucose.units, Some("mg/dl"));
+        assert_eq!(glucose.reference_range, Some("70_105"));
+        assert_eq!(glucose.abnormal_flag, Some("H"));
+
+        // the SN value itself can then be interpreted
+        let value = Field::parse(glucose.value, &msg.get_separators())?.as_structured_numeric();
+        assert_eq!(value.num1, Some(182.0));
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_repeat_count() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rPID|||555-44-4444||EVERYWOMAN^EVE^E^^^^L|JONES|19620320|F|||153 FERNWOOD DR.^^STATESVILLE^OH^35292||(206)3345232~(206)752-121||||AC555444444";
+        let msg = Message::try_from(hl7)?;
+
+        assert_eq!(msg.repeat_count("PID.F13"), 2);
+        assert_eq!(msg.repeat_count("PID.F3"), 1);
+        assert_eq!(msg.repeat_count("PID.F12"), 0); // empty
+        assert_eq!(msg.repeat_count("PID.F30"), 0); // not sent
+        assert_eq!(msg.repeat_count("OBX.F1"), 0); // no segment
+        assert_eq!(msg.repeat_count("MSH.F2"), 1); // contains the repeat char, but isn't split
+        assert_eq!(msg.repeat_count("PID.F13.C1"), 0); // not a field path
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_lower_case_header_is_parsed() -> Result<(), Hl7ParseError> {
+        let msg = Message::try_from("msh|^~\\&|GHH LAB|ELAB-3\rPID|1")?;
+        assert_eq!(msg.get_separators(), Separators::default());
+        assert_eq!(msg.segments[0].kind(), SegmentKind::MSH);
+        assert_eq!(msg.encoding_characters(), "^~\\&");
+
+        // looking segments up by name matches the identifier exactly
+        assert_eq!(msg.query("msh.F3"), "GHH LAB");
+        assert_eq!(msg.query("MSH.F3"), "");
+        assert!(msg.segments_named("MSH").is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_get_field() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|segment|second";
+        let msg = Message::try_from(hl7)?;
+
+        assert_eq!(msg.get_field("OBR", 2), Some("second"));
+        assert_eq!(msg.get_field("OBR", 3), None);
+        assert_eq!(msg.get_field("PID", 1), None);
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_trailing_nuls_are_ignored() -> Result<(), Hl7ParseError> {
         let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|segment";
-        let msg0 = Message::try_from(hl7)?;
-        let msg1 = Message::new(hl7);
+        let padded = format!("{}\0\0\0\0", hl7);
+        let msg = Message::try_from(hl7)?;
+        let padded_msg = Message::try_from(padded.as_str())?;
 
-        assert_eq!(msg0, msg1);
+        assert_eq!(msg.segments.len(), padded_msg.segments.len());
+        assert_eq!(padded_msg.query("OBR.F1"), "segment");
+        assert_eq!(padded_msg.as_str().len(), padded.len());
         Ok(())
     }
 
     #[test]
-    fn ensure_query() -> Result<(), Hl7ParseError> {
+    fn ensure_query_decoded() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|1|845439^GHH OE|1045813^GHH LAB|15545^GLUCOSE|||200202150730|||||||||555-55-5555^PRIMARY^PATRICIA P^^^^MD^^|||||||Joes Obs \\T\\ Gynae||F";
+        let msg = Message::try_from(hl7)?;
+        assert_eq!(msg.query_decoded("OBR.F23"), "Joes Obs & Gynae");
+        assert!(matches!(
+            msg.query_decoded("OBR.F2"),
+            Cow::Borrowed("845439^GHH OE")
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_query_requires_prefixes() -> Result<(), Hl7ParseError> {
         let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|segment^sub&segment";
         let msg = Message::try_from(hl7)?;
-        assert_eq!(msg.query("OBR.F1.R1.C2"), "sub&segment");
-        assert_eq!(msg.query(&*"OBR.F1.R1.C1".to_string()), "segment"); // Test the Into param with a String
-        assert_eq!(msg.query(&*String::from("OBR.F1.R1.C1")), "segment");
-        assert_eq!(msg.query("MSH.F1"), "^~\\&");
+        // well formed
+        assert_eq!(msg.query("OBR.F1"), "segment^sub&segment");
+        assert_eq!(msg.query("OBR.f1.r1.c2"), "sub&segment");
+        assert_eq!(msg.query("OBR.C2.F1"), "sub&segment");
+        // malformed
+        assert_eq!(msg.query("OBR.1"), "");
+        assert_eq!(msg.query("OBR.F1.2"), "");
+        assert_eq!(msg.query("OBR.F"), "");
+        assert_eq!(msg.query("OBR.X1"), "");
+        assert_eq!(msg.query("OBR.F1.F2"), "");
+        assert_eq!(msg.query("OBR..F1"), "");
+        assert_eq!(msg.query(".F1"), "");
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_query_rejects_empty_parts() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|segment^sub&segment";
+        let msg = Message::try_from(hl7)?;
+        assert_eq!(msg.query(""), "");
+        assert_eq!(msg.query("."), "");
+        assert_eq!(msg.query("OBR..F1"), "");
+        assert_eq!(msg.query("OBR.F1..R1"), "");
         Ok(())
     }
 
@@ -377,6 +2228,7 @@ mod tests {
             assert_eq!(msg[String::from("OBR.F1.R1.C1")], "segment");
             assert_eq!(msg[String::from("OBR.F1.C1")], "segment"); // Test missing element in selector
             assert_eq!(msg[String::from("OBR.F1.R1.C2.S1")], "sub");
+            assert_eq!(msg.query("OBR.F1.R1.C2.S1"), "sub");
             println!("{}", Message::parse_query_string("MSH.F2").join("."));
             assert_eq!(msg["MSH.F2"], "^~\\&");
             Ok(())
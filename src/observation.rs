@@ -0,0 +1,111 @@
+use crate::{Field, Segment};
+
+/// A coded element (`CE`/`CWE`), eg OBX-3 `1554-5^GLUCOSE^LN`, identifying a concept within a coding system.
+/// Only the primary coding is exposed here, the alternate coding (components 4 to 6) is still available from the underlying [`Field`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct CodedElement<'a> {
+    /// The code itself, eg `1554-5`
+    pub identifier: &'a str,
+    /// The human readable description, eg `GLUCOSE`
+    pub text: &'a str,
+    /// The coding system the identifier is from (eg `LN` for LOINC), if sent
+    pub coding_system: Option<&'a str>,
+}
+
+impl<'a> CodedElement<'a> {
+    /// Reads the primary coding from the first repeat of the given field
+    pub fn from_field(field: &Field<'a>) -> CodedElement<'a> {
+        CodedElement {
+            identifier: field[(0, 0)],
+            text: field[(0, 1)],
+            coding_system: non_empty(field[(0, 2)]),
+        }
+    }
+}
+
+/// A single observation (eg a lab result) read from an OBX segment, see [`crate::Message::observations()`].
+/// The value is left exactly as sent, as its interpretation depends on the value type (eg see [`Field::as_structured_numeric()`] for `SN` values).
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Observation<'a> {
+    /// OBX-1, the sequence number of this observation within the message
+    pub set_id: &'a str,
+    /// OBX-2, the data type of the value (eg `NM`, `SN`, `ST`)
+    pub value_type: &'a str,
+    /// OBX-3, what was observed
+    pub code: CodedElement<'a>,
+    /// OBX-5, the raw observed value
+    pub value: &'a str,
+    /// The identifier of OBX-6, the units of the value (eg `mg/dl`), if sent
+    pub units: Option<&'a str>,
+    /// OBX-7, the reference (ie normal) range for the value (eg `70-105`), if sent
+    pub reference_range: Option<&'a str>,
+    /// OBX-8, the abnormal flag (eg `H` for high), if sent
+    pub abnormal_flag: Option<&'a str>,
+}
+
+impl<'a> Observation<'a> {
+    /// Reads the observation from the given OBX segment, fields that weren't sent are treated as empty
+    pub fn from_segment(segment: &Segment<'a>) -> Observation<'a> {
+        let field = |idx: usize| segment[idx];
+        let component = |idx: usize| segment.get(idx).map(|f| f[(0, 0)]).unwrap_or("");
+
+        Observation {
+            set_id: field(1),
+            value_type: field(2),
+            code: segment
+                .get(3)
+                .map(CodedElement::from_field)
+                .unwrap_or(CodedElement {
+                    identifier: "",
+                    text: "",
+                    coding_system: None,
+                }),
+            value: field(5),
+            units: non_empty(component(6)),
+            reference_range: non_empty(field(7)),
+            abnormal_flag: non_empty(field(8)),
+        }
+    }
+}
+
+fn non_empty(value: &str) -> Option<&str> {
+    if value.is_empty() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Separators;
+
+    #[test]
+    fn ensure_missing_fields_are_none() {
+        let obx = Segment::parse("OBX|1|ST", &Separators::default()).unwrap();
+        let observation = Observation::from_segment(&obx);
+
+        assert_eq!(observation.set_id, "1");
+        assert_eq!(observation.value_type, "ST");
+        assert_eq!(observation.code.identifier, "");
+        assert_eq!(observation.value, "");
+        assert_eq!(observation.units, None);
+        assert_eq!(observation.abnormal_flag, None);
+    }
+
+    #[test]
+    fn ensure_units_use_the_identifier() {
+        let obx = Segment::parse(
+            "OBX|1|NM|2345-7^GLUCOSE^LN||5.5|mmol/L^millimoles per litre^UCUM|3.9-5.5|N",
+            &Separators::default(),
+        )
+        .unwrap();
+        let observation = Observation::from_segment(&obx);
+
+        assert_eq!(observation.code.coding_system, Some("LN"));
+        assert_eq!(observation.units, Some("mmol/L"));
+        assert_eq!(observation.reference_range, Some("3.9-5.5"));
+        assert_eq!(observation.abnormal_flag, Some("N"));
+    }
+}
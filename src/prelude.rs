@@ -0,0 +1,20 @@
+/*!
+Re-exports the commonly used types (and `TryFrom`, used to parse a [`Message`]) so a single glob import covers most uses of this crate.
+
+## Example:
+```
+use rusthl7::prelude::*;
+# fn main() -> Result<(), Hl7ParseError> {
+let msg = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3\rPID|||555-44-4444||EVERYWOMAN^EVE")?;
+assert_eq!(msg.query("PID.F5.C2"), "EVE");
+
+let delims: Separators = msg.get_separators();
+let escaper = EscapeSequence::new(delims);
+assert_eq!(escaper.decode("Joes Obs \\T\\ Gynae"), "Joes Obs & Gynae");
+# Ok(())
+# }
+```
+*/
+
+pub use crate::{EscapeSequence, Field, Hl7ParseError, Message, Segment, Separators};
+pub use std::convert::TryFrom;
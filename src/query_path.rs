@@ -0,0 +1,211 @@
+use crate::Hl7ParseError;
+use std::convert::TryFrom;
+use std::fmt::Display;
+
+/// A strictly validated query path (see [`crate::Message::query()`] for the grammar), eg `PID.F5.R1.C1`.
+///
+/// Where `query()` quietly returns an empty string for paths it can't interpret, [`QueryPath::parse()`] returns a descriptive error,
+/// which is useful for catching typos in paths that come from configuration or user input.  Unlike `query()` the parts must also be in order
+/// (field, repeat, component, subcomponent).  All indices are 1-based, as per the spec, except that `F0` is allowed for the segment identifier (as per `query()`).
+///
+/// A parsed path displays in the canonical `SEG.Fn.Rn.Cn.Sn` form, with upper case prefixes and no whitespace, so it can be logged or stored
+/// and later parsed back (via `QueryPath::parse()` or `TryFrom<&str>`).  Only the parts that were given are displayed, implied defaults
+/// (eg `R1` when querying a component) aren't filled in, so `PID.F5.C1` stays as-is.
+/// ## Example:
+/// ```
+/// # use rusthl7::Hl7ParseError;
+/// # use rusthl7::QueryPath;
+/// # fn main() -> Result<(), Hl7ParseError> {
+/// let path = QueryPath::parse("PID.F5.C1")?;
+/// assert_eq!(path.segment, "PID");
+/// assert_eq!(path.field, Some(5));
+/// assert_eq!(path.component, Some(1));
+///
+/// assert!(QueryPath::parse("PID.X3").is_err());
+/// assert!(QueryPath::parse("PID.C1.F2").is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct QueryPath<'a> {
+    /// The segment identifier, eg `PID`
+    pub segment: &'a str,
+    /// The field number, if specified
+    pub field: Option<usize>,
+    /// The repeat number, if specified
+    pub repeat: Option<usize>,
+    /// The component number, if specified
+    pub component: Option<usize>,
+    /// The subcomponent number, if specified
+    pub subcomponent: Option<usize>,
+}
+
+impl<'a> QueryPath<'a> {
+    /// Parses and validates the given path, returning an `Err(Hl7ParseError::InvalidQuery)` describing the first problem found.
+    /// Whitespace around each part of the path is ignored.
+    pub fn parse(path: &'a str) -> Result<QueryPath<'a>, Hl7ParseError> {
+        let invalid =
+            |reason: String| Hl7ParseError::InvalidQuery(format!("'{}': {}", path, reason));
+
+        let mut parts = path.split('.').map(str::trim);
+        let segment = match parts.next() {
+            Some(segment) if !segment.is_empty() => segment,
+            _ => return Err(invalid("missing segment identifier".to_string())),
+        };
+
+        let mut query = QueryPath {
+            segment,
+            field: None,
+            repeat: None,
+            component: None,
+            subcomponent: None,
+        };
+
+        // the position of the last part seen, to enforce ordering
+        let mut last = 0;
+        for part in parts {
+            let mut chars = part.chars();
+            let prefix = match chars.next() {
+                Some(c) => c.to_ascii_uppercase(),
+                None => return Err(invalid("empty path part".to_string())),
+            };
+
+            let position = match prefix {
+                'F' => 1,
+                'R' => 2,
+                'C' => 3,
+                'S' => 4,
+                _ => {
+                    return Err(invalid(format!(
+                        "unknown prefix in '{}', expected one of F, R, C or S",
+                        part
+                    )))
+                }
+            };
+            if position <= last {
+                return Err(invalid(format!(
+                    "'{}' is duplicated or out of order, expected F, R, C then S",
+                    part
+                )));
+            }
+            last = position;
+
+            // F0 is the segment identifier, everything else is numbered from 1
+            let min = if position == 1 { 0 } else { 1 };
+            let digits = chars.as_str();
+            let value = match digits.parse::<usize>() {
+                Ok(value) if value >= min && digits.chars().all(|c| c.is_ascii_digit()) => value,
+                _ => {
+                    return Err(invalid(format!(
+                        "'{}' needs a number from {} after the prefix",
+                        part, min
+                    )))
+                }
+            };
+
+            match position {
+                1 => query.field = Some(value),
+                2 => query.repeat = Some(value),
+                3 => query.component = Some(value),
+                _ => query.subcomponent = Some(value),
+            }
+        }
+
+        Ok(query)
+    }
+}
+
+/// Displays the path in its canonical form, eg `PID.F5.C1`
+impl<'a> Display for QueryPath<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.segment)?;
+
+        let parts = [
+            ('F', self.field),
+            ('R', self.repeat),
+            ('C', self.component),
+            ('S', self.subcomponent),
+        ];
+        for (prefix, value) in parts.iter() {
+            if let Some(value) = value {
+                write!(f, ".{}{}", prefix, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parses the path as per [`QueryPath::parse()`].  The parsed path borrows the segment identifier from the input, so `FromStr` (which can't borrow) isn't implemented.
+impl<'a> TryFrom<&'a str> for QueryPath<'a> {
+    type Error = Hl7ParseError;
+
+    fn try_from(path: &'a str) -> Result<Self, Self::Error> {
+        QueryPath::parse(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ensure_valid_paths_parse() -> Result<(), Hl7ParseError> {
+        let path = QueryPath::parse("OBR.F1.R2.C3.S4")?;
+        assert_eq!(
+            path,
+            QueryPath {
+                segment: "OBR",
+                field: Some(1),
+                repeat: Some(2),
+                component: Some(3),
+                subcomponent: Some(4),
+            }
+        );
+
+        let path = QueryPath::parse("PID.F0")?;
+        assert_eq!(path.field, Some(0));
+
+        let path = QueryPath::parse(" pid . f5 ")?;
+        assert_eq!(path.segment, "pid");
+        assert_eq!(path.field, Some(5));
+        assert_eq!(path.repeat, None);
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_unknown_prefix_is_an_error() {
+        assert!(matches!(
+            QueryPath::parse("PID.X3"),
+            Err(Hl7ParseError::InvalidQuery(_))
+        ));
+        assert!(QueryPath::parse("PID.3").is_err());
+        assert!(QueryPath::parse("PID.F").is_err());
+        assert!(QueryPath::parse("PID.F1.R0").is_err());
+        assert!(QueryPath::parse("PID.F2.C0").is_err());
+        assert!(QueryPath::parse("PID..F3").is_err());
+        assert!(QueryPath::parse(".F3").is_err());
+    }
+
+    #[test]
+    fn ensure_display_round_trips_canonical_form() -> Result<(), Hl7ParseError> {
+        assert_eq!(QueryPath::try_from("PID.F5.C1")?.to_string(), "PID.F5.C1");
+        assert_eq!(
+            QueryPath::parse(" PID . f5.r2 .c1.s3 ")?.to_string(),
+            "PID.F5.R2.C1.S3"
+        );
+        assert_eq!(QueryPath::parse("OBR")?.to_string(), "OBR");
+
+        let path = QueryPath::parse("PID.f05.c1")?;
+        let canonical = path.to_string();
+        assert_eq!(canonical, "PID.F5.C1");
+        assert_eq!(QueryPath::parse(&canonical)?, path);
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_out_of_order_is_an_error() {
+        assert!(QueryPath::parse("PID.C1.F2").is_err());
+        assert!(QueryPath::parse("PID.F2.F3").is_err());
+        assert!(QueryPath::parse("PID.F2.C1").is_ok());
+    }
+}
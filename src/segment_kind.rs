@@ -0,0 +1,195 @@
+use std::fmt::Display;
+
+/// The kind of a segment, as determined from it's identifier.  This covers the common standard HL7 V2 segments,
+/// with custom (`Z`) segments and any other identifiers falling back to [`SegmentKind::Z`] and [`SegmentKind::Unknown`] respectively.
+///
+/// This allows for exhaustive `match` based routing of segments rather than comparing strings.
+/// ## Example:
+/// ```
+/// # use rusthl7::SegmentKind;
+/// assert_eq!(SegmentKind::from("PID"), SegmentKind::PID);
+/// assert_eq!(SegmentKind::from("ZPD"), SegmentKind::Z("ZPD"));
+/// assert_eq!(SegmentKind::from("XYZ"), SegmentKind::Unknown("XYZ"));
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum SegmentKind<'a> {
+    /// Message Header
+    MSH,
+    /// Message Acknowledgment
+    MSA,
+    /// Error
+    ERR,
+    /// Software
+    SFT,
+    /// Event Type
+    EVN,
+    /// Patient Identification
+    PID,
+    /// Patient Additional Demographic
+    PD1,
+    /// Next of Kin / Associated Parties
+    NK1,
+    /// Merge Patient Information
+    MRG,
+    /// Patient Visit
+    PV1,
+    /// Patient Visit - Additional Information
+    PV2,
+    /// Patient Allergy Information
+    AL1,
+    /// Diagnosis
+    DG1,
+    /// Procedures
+    PR1,
+    /// Role
+    ROL,
+    /// Guarantor
+    GT1,
+    /// Insurance
+    IN1,
+    /// Common Order
+    ORC,
+    /// Timing/Quantity
+    TQ1,
+    /// Observation Request
+    OBR,
+    /// Observation/Result
+    OBX,
+    /// Notes and Comments
+    NTE,
+    /// Specimen
+    SPM,
+    /// Financial Transaction
+    FT1,
+    /// A custom segment, identifier starting with `Z`
+    Z(&'a str),
+    /// Any identifier not otherwise known to this library
+    Unknown(&'a str),
+}
+
+impl<'a> SegmentKind<'a> {
+    /// Returns the identifier for this kind of segment, eg `"PID"`
+    pub fn as_str(&self) -> &'a str {
+        match self {
+            SegmentKind::MSH => "MSH",
+            SegmentKind::MSA => "MSA",
+            SegmentKind::ERR => "ERR",
+            SegmentKind::SFT => "SFT",
+            SegmentKind::EVN => "EVN",
+            SegmentKind::PID => "PID",
+            SegmentKind::PD1 => "PD1",
+            SegmentKind::NK1 => "NK1",
+            SegmentKind::MRG => "MRG",
+            SegmentKind::PV1 => "PV1",
+            SegmentKind::PV2 => "PV2",
+            SegmentKind::AL1 => "AL1",
+            SegmentKind::DG1 => "DG1",
+            SegmentKind::PR1 => "PR1",
+            SegmentKind::ROL => "ROL",
+            SegmentKind::GT1 => "GT1",
+            SegmentKind::IN1 => "IN1",
+            SegmentKind::ORC => "ORC",
+            SegmentKind::TQ1 => "TQ1",
+            SegmentKind::OBR => "OBR",
+            SegmentKind::OBX => "OBX",
+            SegmentKind::NTE => "NTE",
+            SegmentKind::SPM => "SPM",
+            SegmentKind::FT1 => "FT1",
+            SegmentKind::Z(id) | SegmentKind::Unknown(id) => id,
+        }
+    }
+}
+
+impl<'a> From<&'a str> for SegmentKind<'a> {
+    /// Maps a segment identifier to it's kind.  This conversion cannot fail, unrecognised identifiers are returned as `Z` or `Unknown`.
+    /// Matching is case-insensitive as some systems send lower case identifiers (eg `msh`), the original case is kept for `Z` and `Unknown`.
+    fn from(identifier: &'a str) -> Self {
+        // identifiers are always 3 chars, so upper case them on the stack rather than allocating
+        let mut buf = [0u8; 3];
+        let upper = if identifier.len() == buf.len() && identifier.is_ascii() {
+            buf.copy_from_slice(identifier.as_bytes());
+            buf.make_ascii_uppercase();
+            std::str::from_utf8(&buf).unwrap_or(identifier)
+        } else {
+            identifier
+        };
+
+        match upper {
+            "MSH" => SegmentKind::MSH,
+            "MSA" => SegmentKind::MSA,
+            "ERR" => SegmentKind::ERR,
+            "SFT" => SegmentKind::SFT,
+            "EVN" => SegmentKind::EVN,
+            "PID" => SegmentKind::PID,
+            "PD1" => SegmentKind::PD1,
+            "NK1" => SegmentKind::NK1,
+            "MRG" => SegmentKind::MRG,
+            "PV1" => SegmentKind::PV1,
+            "PV2" => SegmentKind::PV2,
+            "AL1" => SegmentKind::AL1,
+            "DG1" => SegmentKind::DG1,
+            "PR1" => SegmentKind::PR1,
+            "ROL" => SegmentKind::ROL,
+            "GT1" => SegmentKind::GT1,
+            "IN1" => SegmentKind::IN1,
+            "ORC" => SegmentKind::ORC,
+            "TQ1" => SegmentKind::TQ1,
+            "OBR" => SegmentKind::OBR,
+            "OBX" => SegmentKind::OBX,
+            "NTE" => SegmentKind::NTE,
+            "SPM" => SegmentKind::SPM,
+            "FT1" => SegmentKind::FT1,
+            z if z.starts_with('Z') => SegmentKind::Z(identifier),
+            _ => SegmentKind::Unknown(identifier),
+        }
+    }
+}
+
+impl<'a> Display for SegmentKind<'a> {
+    /// Required for to_string() and other formatter consumers.  This returns the segment identifier.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Hl7ParseError, Segment, Separators};
+
+    #[test]
+    fn ensure_known_identifiers_map() {
+        assert_eq!(SegmentKind::from("MSH"), SegmentKind::MSH);
+        assert_eq!(SegmentKind::from("OBX"), SegmentKind::OBX);
+        assert_eq!(SegmentKind::from("SPM"), SegmentKind::SPM);
+        assert_eq!(SegmentKind::OBX.as_str(), "OBX");
+    }
+
+    #[test]
+    fn ensure_unknown_identifiers_fall_back() {
+        assert_eq!(SegmentKind::from("ZPD"), SegmentKind::Z("ZPD"));
+        assert_eq!(SegmentKind::from("QQQ"), SegmentKind::Unknown("QQQ"));
+        assert_eq!(SegmentKind::from("QQQ").to_string(), "QQQ");
+    }
+
+    #[test]
+    fn ensure_identifiers_match_case_insensitively() -> Result<(), Hl7ParseError> {
+        assert_eq!(SegmentKind::from("msh"), SegmentKind::MSH);
+        assert_eq!(SegmentKind::from("Pid"), SegmentKind::PID);
+        assert_eq!(SegmentKind::from("zpd"), SegmentKind::Z("zpd"));
+        assert_eq!(SegmentKind::from("qqq"), SegmentKind::Unknown("qqq"));
+        assert_eq!(SegmentKind::from("ms"), SegmentKind::Unknown("ms"));
+
+        let segment = Segment::parse("msh|^~\\&|GHH LAB|ELAB-3", &Separators::default())?;
+        assert_eq!(segment.kind(), SegmentKind::MSH);
+        assert_eq!(segment.identifier(), "msh"); // the source is untouched
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_segment_kind() -> Result<(), Hl7ParseError> {
+        let segment = Segment::parse("PV1|1|I", &Separators::default())?;
+        assert_eq!(segment.kind(), SegmentKind::PV1);
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
-use crate::{Field, Hl7ParseError, Separators};
+use crate::{EscapeSequence, Field, Hl7ParseError, SegmentKind, Separators};
+use std::borrow::Cow;
 use std::fmt::Display;
 use std::ops::Index;
 
@@ -6,13 +7,16 @@ use std::ops::Index;
 #[derive(Debug, PartialEq, Clone)]
 pub struct Segment<'a> {
     pub source: &'a str,
-    delim: char,
+    pub(crate) delims: Separators,
     pub fields: Vec<Field<'a>>,
+    /// The field separator as sent directly after the identifier (ie MSH-1 for the header), or empty if there wasn't one
+    field_separator: &'a str,
 }
 
 impl<'a> Segment<'a> {
     /// Convert the given line of text into a Segment.  NOTE: This is not normally needed to be called directly by
-    /// consumers but is used indirectly via [`crate::Message::new()`].
+    /// consumers but is used indirectly via `Message::try_from()`.
+    /// Returns an `Err(Hl7ParseError::Generic)` if the segment has an empty identifier (eg a blank line).
     pub fn parse<S: Into<&'a str>>(
         input: S,
         delims: &Separators,
@@ -25,10 +29,23 @@ impl<'a> Segment<'a> {
                 .collect();
 
             let fields = fields?;
+            if fields[0].source.is_empty() {
+                return Err(Hl7ParseError::Generic(format!(
+                    "Segment has an empty identifier: '{}'",
+                    input
+                )));
+            }
+
+            // the identifier runs up to the first field separator
+            let field_separator = input[fields[0].source.len()..]
+                .get(..delims.field.len_utf8())
+                .unwrap_or("");
+
             let seg = Segment {
                 source: input,
-                delim: delims.segment,
+                delims: *delims,
                 fields,
+                field_separator,
             };
             Ok(seg)
         }
@@ -49,10 +66,33 @@ impl<'a> Segment<'a> {
     /// # }
     /// ```
     /// eg a segment `EVN||200708181123||` has an identifer of `EVN`.
+    ///
+    /// The identifier is returned exactly as sent.  Only [`Segment::kind()`] (and the MSH and Z segment handling built on it) ignores case,
+    /// looking segments up by name (eg [`crate::Message::query()`] or [`crate::Message::segments_named()`]) matches the identifier exactly.
     pub fn identifier(&self) -> &'a str {
         self.fields[0].source
     }
 
+    /// Get the [`SegmentKind`] for this segment, as determined from it's identifier.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::{Segment, SegmentKind, Separators};
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let segment = Segment::parse("OBR|field1|field2", &Separators::default())?;
+    /// assert_eq!(SegmentKind::OBR, segment.kind());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn kind(&self) -> SegmentKind<'a> {
+        SegmentKind::from(self.identifier())
+    }
+
+    /// Returns true if this is the MSH (header) segment.  As per [`Segment::kind()`] the identifier is matched case-insensitively.
+    pub(crate) fn is_msh(&self) -> bool {
+        self.identifier().eq_ignore_ascii_case("MSH")
+    }
+
     /// Returns the original `&str` used to initialise this Segment.  This method does not allocate.
     /// ## Example:
     /// ```
@@ -72,37 +112,313 @@ impl<'a> Segment<'a> {
         self.source
     }
 
-    /// Access Field as string reference
+    /// Returns the number of fields in this segment, _including_ the identifier at index 0.
+    /// This matches the bounds used by the numeric indexers, so `0..segment.len()` is always safe to index.
+    ///
+    /// This is the raw split count rather than a spec field number.  MSH-1 (the field separator) isn't split out as a field,
+    /// so for an MSH segment the last field is MSH-`len()`, rather than `len() - 1` as for every other segment.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::{Segment, Separators};
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let segment = Segment::parse("OBR|1|2", &Separators::default())?;
+    /// assert_eq!(3, segment.len());
+    ///
+    /// let msh = Segment::parse("MSH|^~\\&|GHH LAB", &Separators::default())?;
+    /// assert_eq!(3, msh.len());
+    /// assert_eq!("GHH LAB", msh.query("F3")); // MSH-3 is the last field
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Returns true if this segment has no fields (not even an identifier)
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// As per [`Segment::query()`], but distinguishes a value that is present but empty (`Some("")`) from one that doesn't exist at all (`None`).
+    /// See [`Field::try_query()`].
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::{Segment, Separators};
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let segment = Segment::parse("OBX|1|a^^c", &Separators::default())?;
+    /// assert_eq!(segment.try_query("F2.R1.C2"), Some(""));
+    /// assert_eq!(segment.try_query("F2.R1.C9"), None);
+    /// assert_eq!(segment.try_query("F9"), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn try_query<'b, S>(&self, fidx: S) -> Option<&'a str>
+    where
+        S: Into<&'b str>,
+    {
+        let fidx = fidx.into();
+        let sections = fidx.split('.').collect::<Vec<&str>>();
+        self.try_query_parts(&sections)
+    }
+
+    /// As per [`Segment::try_query()`], but taking the path already split into it's parts (eg `["F3", "R1", "C2"]`).
+    pub fn try_query_parts(&self, parts: &[&str]) -> Option<&'a str> {
+        self.query_ref(parts).copied()
+    }
+
+    /// As per [`Segment::try_query_parts()`], but returning a reference to the stored value, as needed by the string `Index` impls.
+    pub(crate) fn query_ref(&self, parts: &[&str]) -> Option<&&'a str> {
+        let (field_part, rest) = parts.split_first()?;
+
+        let stringnum = field_part
+            .chars()
+            .filter(|c| c.is_ascii_digit())
+            .collect::<String>();
+        let idx: usize = stringnum.parse().ok()?;
+
+        if rest.is_empty() {
+            self.field_ref(idx)
+        } else if self.is_msh_encoding_chars(idx) {
+            Self::query_encoding_chars(self.field_ref(idx)?, rest)
+        } else {
+            self.fields.get(self.field_position(idx)?)?.query_ref(rest)
+        }
+    }
+
+    /// Maps a field number, as used in query paths, to it's position in `fields`.
+    /// MSH-1 (the field separator) isn't split out as a field, so MSH fields are shifted down by one (MSH-2 is at position 1) and MSH-1 itself has no position.
+    pub(crate) fn field_position(&self, number: usize) -> Option<usize> {
+        if number == 0 || !self.is_msh() {
+            Some(number)
+        } else {
+            Some(number - 1).filter(|p| *p > 0)
+        }
+    }
+
+    /// Gets the value of the given field number (as used in query paths), including MSH-1, or `None` if the field wasn't sent.
+    pub(crate) fn field_value(&self, number: usize) -> Option<&'a str> {
+        self.field_ref(number).copied()
+    }
+
+    /// As per `field_value()`, but returning a reference to the stored value.
+    fn field_ref(&self, number: usize) -> Option<&&'a str> {
+        if number == 1 && self.is_msh() {
+            return Some(&self.field_separator).filter(|s| !s.is_empty());
+        }
+        self.fields
+            .get(self.field_position(number)?)
+            .map(|f| &f.source)
+    }
+
+    /// Returns true if the given field number is MSH-2, the encoding chars (ie `^~\&`)
+    pub(crate) fn is_msh_encoding_chars(&self, number: usize) -> bool {
+        number == 2 && self.is_msh()
+    }
+
+    /// Resolves a path into MSH-2.  The encoding chars define the separators (and so contain the repeat and component chars themselves),
+    /// so they're never split: the field is its own single repeat (`R1`), and any other path into it doesn't exist.
+    fn query_encoding_chars<T>(value: T, rest: &[&str]) -> Option<T> {
+        match rest {
+            [part] if part.eq_ignore_ascii_case("R1") => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the given field was actually sent in this segment, even if it was sent empty.
+    ///
+    /// HL7 allows trailing optional fields to simply be left off the end of a segment, so `PID|1||` _sends_ fields 2 and 3 (empty), whereas `PID|1` doesn't send them at all.
+    /// Both the numeric indexer and [`Segment::query()`] return `""` in either case, use this method when the distinction matters.
+    /// Fields are numbered as per [`Segment::query()`], so MSH fields follow the spec numbering (MSH-1 is the field separator).
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::{Segment, Separators};
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let segment = Segment::parse("PID|1||", &Separators::default())?;
+    /// assert!(segment.field_present(3)); // sent, but empty
+    /// assert!(!segment.field_present(4)); // not sent
+    ///
+    /// let msh = Segment::parse("MSH|^~\\&|GHH LAB", &Separators::default())?;
+    /// assert!(msh.field_present(3));
+    /// assert!(!msh.field_present(4));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn field_present(&self, number: usize) -> bool {
+        self.field_value(number).is_some()
+    }
+
+    /// Gets the field at the given index (as per indexing, so 0 is the identifier), or `None` if the segment doesn't have that many fields.
+    /// This is the non-panicking alternative to indexing into `fields` directly.
+    ///
+    /// As with [`Segment::len()`] this is the raw position in `fields` rather than a spec field number, so for an MSH segment `get(n)` is MSH-`n + 1`
+    /// (MSH-1, the field separator, isn't split out as a field).
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::{Segment, Separators};
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let segment = Segment::parse("OBR|1|2", &Separators::default())?;
+    /// assert_eq!(segment.get(2).map(|f| f.as_str()), Some("2"));
+    /// assert!(segment.get(3).is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&Field<'a>> {
+        self.fields.get(index)
+    }
+
+    /// Returns the raw source of each field in this segment, starting with the identifier, which is handy for tabular (eg CSV) export.
+    /// This is the per-segment equivalent of [`crate::Message::segments_to_str_vecs()`].  Note that for an MSH the field separator itself (MSH-1) isn't included.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::{Segment, Separators};
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let segment = Segment::parse("OBR|1||15545^GLUCOSE", &Separators::default())?;
+    /// assert_eq!(segment.field_values(), vec!["OBR", "1", "", "15545^GLUCOSE"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn field_values(&self) -> Vec<&'a str> {
+        self.fields.iter().map(|f| f.source).collect()
+    }
+
+    /// Serialises this segment with exactly `count` fields after the identifier, padding with empty fields or truncating as required.
+    /// This is useful for downstream systems that require a fixed (or minimum) number of fields.
+    ///
+    /// `count` follows the spec numbering, so for an MSH segment it includes MSH-1 (the field separator) and the result ends at MSH-`count`.
+    /// MSH-1 and MSH-2 define the separators and so are always kept.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::{Segment, Separators};
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let segment = Segment::parse("OBR|1|2", &Separators::default())?;
+    /// assert_eq!("OBR|1|2||", segment.to_string_with_field_count(4));
+    /// assert_eq!("OBR|1", segment.to_string_with_field_count(1));
+    ///
+    /// let msh = Segment::parse("MSH|^~\\&|GHH LAB", &Separators::default())?;
+    /// assert_eq!("MSH|^~\\&|GHH LAB||", msh.to_string_with_field_count(5));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_string_with_field_count(&self, count: usize) -> String {
+        let separator = self.delims.field.to_string();
+        // +1 for the identifier, but MSH-1 isn't split out as a field so is already covered by the separator
+        let take = if self.is_msh() {
+            count.max(2)
+        } else {
+            count + 1
+        };
+        let fields: Vec<&str> = self
+            .fields
+            .iter()
+            .map(|f| f.source)
+            .chain(std::iter::repeat(""))
+            .take(take)
+            .collect();
+        fields.join(&separator)
+    }
+
+    /// Access Field as string reference.
+    /// Fields are numbered from 1 (eg `F1`), with `F0` reliably returning the segment identifier (including for `MSH`).
+    /// MSH fields follow the spec numbering, so `F1` is the field separator and `F2` the encoding chars.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::{Segment, Separators};
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let segment = Segment::parse("PID|||555-44-4444", &Separators::default())?;
+    /// assert_eq!("PID", segment.query("F0"));
+    /// assert_eq!("555-44-4444", segment.query("F3"));
+    /// # Ok(())
+    /// # }
+    /// ```
     pub fn query<'b, S>(&self, fidx: S) -> &'a str
     where
         S: Into<&'b str>,
     {
         let fidx = fidx.into();
         let sections = fidx.split('.').collect::<Vec<&str>>();
+        self.query_parts(&sections)
+    }
 
-        match sections.len() {
-            1 => {
-                let stringnum = sections[0]
-                    .chars()
-                    .filter(|c| c.is_ascii_digit())
-                    .collect::<String>();
-                let idx: usize = stringnum.parse().unwrap();
-                self[idx]
-            }
-            _ => {
-                let stringnum = sections[0]
-                    .chars()
-                    .filter(|c| c.is_ascii_digit())
-                    .collect::<String>();
-                let idx: usize = stringnum.parse().unwrap();
-                if idx > self.fields.len() - 1 {
-                    return "";
-                }
-                let field = &self.fields[idx];
-                let query = sections[1..].join(".");
-
-                field.query(&*query)
-            }
+    /// Gets the separators this segment was parsed with (see [`crate::Message::get_separators()`]).
+    pub fn separators(&self) -> Separators {
+        self.delims
+    }
+
+    /// Queries for the value at the given path (see [`Segment::query()`]) and then decodes any escape sequences in the result
+    /// using the separators this segment was parsed with, so there's no need to thread the message's separators through.
+    /// This only allocates if the value contains escape sequences, see [`crate::Message::query_decoded()`] for more on the cost of decoding.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::{Segment, Separators};
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let segment = Segment::parse("OBR|1|Joes Obs \\T\\ Gynae", &Separators::default())?;
+    /// assert_eq!(segment.query_decoded("F2"), "Joes Obs & Gynae");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query_decoded<'b, S>(&self, fidx: S) -> Cow<'a, str>
+    where
+        S: Into<&'b str>,
+    {
+        let value = self.query(fidx);
+        if self.delims == Separators::DEFAULT {
+            EscapeSequence::default().decode(value)
+        } else {
+            EscapeSequence::new(self.delims).decode(value)
+        }
+    }
+
+    /// As per [`Segment::query()`], but taking the path already split into it's parts (eg `["F3", "R1", "C2"]`).
+    /// This avoids rebuilding the remaining path as a `String` at each level when drilling down from [`crate::Message::query()`].
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::{Segment, Separators};
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let segment = Segment::parse("PID|||555-44-4444||EVERYWOMAN^EVE", &Separators::default())?;
+    /// assert_eq!("EVE", segment.query_parts(&["F5", "R1", "C2"]));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query_parts(&self, parts: &[&str]) -> &'a str {
+        let (field_part, rest) = match parts.split_first() {
+            Some(split) => split,
+            None => return "",
+        };
+
+        let stringnum = field_part
+            .chars()
+            .filter(|c| c.is_ascii_digit())
+            .collect::<String>();
+        let idx: usize = match stringnum.parse() {
+            Ok(idx) => idx,
+            Err(_) => return "", // no digits, eg `FX`
+        };
+
+        if rest.is_empty() {
+            return self.field_value(idx).unwrap_or("");
+        }
+        if self.is_msh_encoding_chars(idx) {
+            return self
+                .field_value(idx)
+                .and_then(|value| Self::query_encoding_chars(value, rest))
+                .unwrap_or("");
+        }
+        match self.field_position(idx).and_then(|p| self.fields.get(p)) {
+            Some(field) => field.query_parts(rest),
+            None => "",
         }
     }
 }
@@ -114,11 +430,18 @@ impl<'a> Display for Segment<'a> {
     }
 }
 
+impl<'a> AsRef<str> for Segment<'a> {
+    /// Returns the source string for this segment, allowing it to be passed directly to APIs taking `impl AsRef<str>`
+    fn as_ref(&self) -> &str {
+        self.source
+    }
+}
+
 impl<'a> Index<usize> for Segment<'a> {
     type Output = &'a str;
     /// Access Field as string reference
     fn index(&self, fidx: usize) -> &Self::Output {
-        if fidx > self.fields.len() - 1 {
+        if fidx >= self.fields.len() {
             return &"";
         };
         &self.fields[fidx].source
@@ -129,7 +452,8 @@ impl<'a> Index<(usize, usize)> for Segment<'a> {
     type Output = &'a str;
     /// Access Field component as string reference
     fn index(&self, fidx: (usize, usize)) -> &Self::Output {
-        if fidx.0 > self.fields.len() - 1 || fidx.1 > self.fields[fidx.0].components.len() - 1 {
+        // the field indexer guards the inner index
+        if fidx.0 >= self.fields.len() {
             return &"";
         }
         &self.fields[fidx.0][fidx.1]
@@ -140,10 +464,8 @@ impl<'a> Index<(usize, usize, usize)> for Segment<'a> {
     type Output = &'a str;
     /// Access Field subcomponent as string reference
     fn index(&self, fidx: (usize, usize, usize)) -> &Self::Output {
-        if fidx.0 > self.fields.len() - 1
-            || fidx.1 > self.fields[fidx.0].components.len() - 1
-            || fidx.2 > self.fields[fidx.0].subcomponents[fidx.1].len() - 1
-        {
+        // the field indexer guards the inner indices
+        if fidx.0 >= self.fields.len() {
             return &"";
         }
         &self.fields[fidx.0][(fidx.1, fidx.2)]
@@ -153,35 +475,10 @@ impl<'a> Index<(usize, usize, usize)> for Segment<'a> {
 #[cfg(feature = "string_index")]
 impl<'a> Index<&str> for Segment<'a> {
     type Output = &'a str;
-    /// Access Field as string reference
+    /// Access Field as string reference, resolved as per [`Segment::query()`] (including the MSH field numbering)
     fn index(&self, fidx: &str) -> &Self::Output {
         let sections = fidx.split('.').collect::<Vec<&str>>();
-        let stringnum = sections[0]
-            .chars()
-            .filter(|c| c.is_digit(10))
-            .collect::<String>();
-        let mut idx: usize = stringnum.parse().unwrap();
-        // MSH segment has an off-by-one problem in that the first
-        // field separator is considered to be a field in the spec
-        // https://hl7-definition.caristix.com/v2/HL7v2.8/Segments/MSH
-        if self.fields[0].source == "MSH" {
-            if idx == 1 {
-                // return &&self.source[3..3]; //TODO figure out how to return a string ref safely
-                return &"|";
-            } else {
-                idx = idx - 1
-            }
-        }
-        match sections.len() {
-            1 => &self[idx],
-            _ => {
-                if idx < self.fields.len() {
-                    &self.fields[idx][sections[1..].join(".")]
-                } else {
-                    &""
-                }
-            }
-        }
+        self.query_ref(&sections).unwrap_or(&"")
     }
 }
 
@@ -197,7 +494,7 @@ impl<'a> Index<String> for Segment<'a> {
 
 #[cfg(test)]
 mod tests {
-    use crate::Message;
+    use crate::{Hl7ParseError, Message, Segment, Separators};
     use std::convert::TryFrom;
 
     #[test]
@@ -228,6 +525,203 @@ mod tests {
         assert_eq!(oob, "");
     }
 
+    #[test]
+    fn ensure_f0_queries_identifier() {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rPID|||555-44-4444";
+        let msg = Message::try_from(hl7).unwrap();
+        assert_eq!(msg.segments[0].query("F0"), "MSH");
+        assert_eq!(msg.segments[1].query("F0"), "PID");
+        assert_eq!(msg.query("PID.F0"), "PID");
+    }
+
+    #[test]
+    fn ensure_query_past_end_of_segment_is_empty() {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|segment";
+        let msg = Message::try_from(hl7).unwrap();
+        let x = &msg.segments[1];
+        assert_eq!(x.query("F99"), "");
+        assert_eq!(x.query("F99.R1"), "");
+        assert_eq!(x.query("F2.R1.C1"), "");
+    }
+
+    #[test]
+    fn ensure_len_counts_fields() {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rOBR|1|845439^GHH OE|1045813^GHH LAB|15545^GLUCOSE";
+        let msg = Message::try_from(hl7).unwrap();
+        let obr = &msg.segments[1];
+
+        assert_eq!(obr.len(), 5);
+        assert!(!obr.is_empty());
+        for i in 0..obr.len() {
+            assert_eq!(obr[i], obr.fields[i].source);
+        }
+
+        // MSH-1 isn't split out, so the raw count is one less than the spec numbering implies
+        let msh = &msg.segments[0];
+        assert_eq!(msh.len(), 12);
+        assert_eq!(msh.query(format!("F{}", msh.len()).as_str()), "2.4");
+        assert_eq!(msh[msh.len() - 1], "2.4");
+    }
+
+    #[test]
+    fn ensure_present_empty_fields_differ_from_absent() {
+        let trailing_empty = Segment::parse("OBR|1|2||", &Separators::default()).unwrap();
+        let trailing_absent = Segment::parse("OBR|1|2", &Separators::default()).unwrap();
+
+        // indistinguishable by value...
+        assert_eq!(trailing_empty[4], "");
+        assert_eq!(trailing_absent[4], "");
+
+        // ...but not by presence
+        assert!(trailing_empty.field_present(4));
+        assert!(!trailing_empty.field_present(5));
+        assert!(trailing_absent.field_present(2));
+        assert!(!trailing_absent.field_present(3));
+
+        // MSH follows the spec numbering, as per `query()`
+        let msh = Segment::parse("MSH|^~\\&|A|B", &Separators::default()).unwrap();
+        assert!(msh.field_present(1));
+        assert!(msh.field_present(4));
+        assert_eq!(msh.query("F4"), "B");
+        assert!(!msh.field_present(5));
+        assert_eq!(msh.get(3).map(|f| f.as_str()), Some("B"));
+    }
+
+    #[test]
+    fn ensure_get_is_bounds_checked() {
+        let segment = Segment::parse("OBR|1|a^b", &Separators::default()).unwrap();
+
+        assert_eq!(segment.get(0).unwrap().as_str(), "OBR");
+        assert_eq!(segment.get(2).unwrap()[(0, 1)], "b");
+        assert!(segment.get(3).is_none());
+        assert!(segment.get(usize::MAX).is_none());
+    }
+
+    #[test]
+    fn ensure_segment_decodes_with_its_own_separators() -> Result<(), Hl7ParseError> {
+        let delims: Separators = "MSH|^~#&|".parse()?; // non-default escape char
+        let segment = Segment::parse("OBR|1|Joes Obs #T# Gynae^#S#2", &delims)?;
+
+        assert_eq!(segment.separators(), delims);
+        assert_eq!(segment.query_decoded("F2.R1.C1"), "Joes Obs & Gynae");
+        assert_eq!(segment.query_decoded("F2.R1.C2"), "^2");
+        assert_eq!(segment.query_decoded("F1"), "1");
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_paths_without_digits_are_empty() {
+        let segment = Segment::parse("PID|1|a~b^c", &Separators::default()).unwrap();
+
+        assert_eq!(segment.query("F1.XY"), "");
+        assert_eq!(segment.query("F2.RC"), "");
+        assert_eq!(segment.query("F2.R1.CX"), "");
+        assert_eq!(segment.query("F2.R0"), "");
+        assert_eq!(segment.query("FX"), "");
+        assert_eq!(segment.query("F2.R2.C2"), "c");
+    }
+
+    #[test]
+    fn ensure_field_values_match_message_vecs() {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3\rOBR|1|845439^GHH OE|1045813^GHH LAB|15545^GLUCOSE|||200202150730";
+        let msg = Message::try_from(hl7).unwrap();
+        let obr = &msg.segments[1];
+
+        assert_eq!(
+            obr.field_values(),
+            vec![
+                "OBR",
+                "1",
+                "845439^GHH OE",
+                "1045813^GHH LAB",
+                "15545^GLUCOSE",
+                "",
+                "",
+                "200202150730"
+            ]
+        );
+        assert_eq!(
+            Message::segments_to_str_vecs(vec![obr]).unwrap()[0],
+            obr.field_values()
+        );
+    }
+
+    #[test]
+    fn ensure_lower_case_msh_uses_msh_numbering() -> Result<(), Hl7ParseError> {
+        let segment = Segment::parse("msh|^~\\&|GHH LAB|ELAB-3", &Separators::default())?;
+        assert_eq!(segment.query("F1"), "|");
+        assert_eq!(segment.query("F2"), "^~\\&");
+        assert_eq!(segment.query("F2.C1"), "");
+        assert_eq!(segment.query("F3"), "GHH LAB");
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_msh_encoding_chars_are_not_drilled_into() {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rPID|1|2^3";
+        let msg = Message::try_from(hl7).unwrap();
+        let msh = &msg.segments[0];
+
+        assert_eq!(msh.query("F2"), "^~\\&");
+        assert_eq!(msh.query("F2.R1"), "^~\\&");
+        assert_eq!(msh.query("F2.R2"), "");
+        assert_eq!(msh.query("F2.R2.C1"), "");
+        assert_eq!(msh.try_query("F2.R1.C1"), None);
+        assert_eq!(msg.query("MSH.F2"), "^~\\&");
+        assert_eq!(msg.query("MSH.F2.R1"), "^~\\&");
+        assert_eq!(msg.try_query("MSH.F2.R1"), Some("^~\\&"));
+        assert_eq!(msg.query("MSH.F2.C1"), "");
+        assert_eq!(msg.query("MSH.F1.C1"), "");
+        // other segments are unaffected
+        assert_eq!(msg.query("PID.F2.C2"), "3");
+    }
+
+    #[test]
+    fn ensure_tuple_index_boundaries() {
+        let segment = Segment::parse("OBR|a^b~c|d", &Separators::default()).unwrap();
+
+        // field, repeat
+        assert_eq!(segment[(1, 1)], "c");
+        assert_eq!(segment[(1, 2)], "");
+        assert_eq!(segment[(2, 0)], "d");
+        assert_eq!(segment[(3, 0)], "");
+
+        // field, repeat, component
+        assert_eq!(segment[(1, 0, 1)], "b");
+        assert_eq!(segment[(1, 0, 2)], "");
+        assert_eq!(segment[(1, 1, 0)], "c");
+        assert_eq!(segment[(1, 2, 0)], "");
+        assert_eq!(segment[(3, 0, 0)], "");
+    }
+
+    #[test]
+    fn ensure_empty_identifier_is_rejected() {
+        assert!(Segment::parse("", &Separators::default()).is_err());
+        assert!(Segment::parse("|1|2", &Separators::default()).is_err());
+    }
+
+    #[test]
+    fn ensure_field_count_normalisation() {
+        let segment = Segment::parse("OBR|1|2|3|4", &Separators::default()).unwrap();
+
+        let padded = segment.to_string_with_field_count(8);
+        assert_eq!(padded, "OBR|1|2|3|4||||");
+        assert_eq!(padded.matches('|').count(), 8);
+
+        assert_eq!(segment.to_string_with_field_count(2), "OBR|1|2");
+        assert_eq!(segment.to_string_with_field_count(4), segment.as_str());
+
+        // MSH counts follow the spec numbering, so include MSH-1
+        let msh = Segment::parse("MSH|^~\\&|GHH LAB|ELAB-3", &Separators::default()).unwrap();
+        assert_eq!(msh.to_string_with_field_count(4), msh.as_str());
+        assert_eq!(
+            msh.to_string_with_field_count(6),
+            "MSH|^~\\&|GHH LAB|ELAB-3||"
+        );
+        assert_eq!(msh.to_string_with_field_count(3), "MSH|^~\\&|GHH LAB");
+        assert_eq!(msh.to_string_with_field_count(0), "MSH|^~\\&");
+    }
+
     #[cfg(feature = "string_index")]
     mod string_index_tests {
         use super::*;
@@ -247,5 +741,25 @@ mod tests {
             assert_eq!(s, "segment");
             assert_eq!(oob, "");
         }
+
+        #[test]
+        fn ensure_string_index_matches_query() -> Result<(), Hl7ParseError> {
+            let msh = Segment::parse(
+                "MSH#^~\\&#GHH LAB^ELAB-3#GHH OE",
+                &str::parse::<Separators>("MSH#^~\\&#")?,
+            )?;
+            let paths = [
+                "F1", "F2", "F2.R1", "F2.C1", "F2.R1.C1", "F3.R1.C2", "F0", "F3.C0", "FX", "F99",
+                "",
+            ];
+            for path in paths.iter() {
+                assert_eq!(msh[*path], msh.query(*path), "{}", path);
+            }
+            assert_eq!(msh["F1"], "#");
+            assert_eq!(msh["F2"], "^~\\&");
+            assert_eq!(msh["F2.C1"], "");
+            assert_eq!(msh["F3.C0"], "");
+            Ok(())
+        }
     }
 }
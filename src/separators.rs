@@ -5,7 +5,7 @@ use std::str::FromStr;
 /// A helper struct to store the separator (delimiter) characters used to parse this message.
 /// Note that HL7 allows each _message_ to define it's own separators, although most messages
 /// use a default set (available from [`Separators::default()`])
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub struct Separators {
     /// constant value, spec fixed to '\r' (ASCII 13, 0x0D)
     pub segment: char,
@@ -22,45 +22,161 @@ pub struct Separators {
 }
 
 impl Default for Separators {
-	/// Create a Separator with the default (most common) HL7 values
+    /// Create a Separator with the default (most common) HL7 values
     fn default() -> Separators {
-        Separators {
-            segment: '\r',
-            field: '|',
-            repeat: '~',
-            component: '^',
-            subcomponent: '&',
-            escape_char: '\\',
-        }
+        Separators::DEFAULT
     }
 }
 
 impl Separators {
+    /// The default (most common) HL7 separator values, usable in `const` contexts.
+    /// This is the same value returned by [`Separators::default()`].
+    pub const DEFAULT: Separators = Separators {
+        segment: '\r',
+        field: '|',
+        repeat: '~',
+        component: '^',
+        subcomponent: '&',
+        escape_char: '\\',
+    };
 
     // Create a Separators with the values provided in the message.
     // This assumes the message starts with `MSH|^~\&|` or equiv for custom Separators
     fn new(message: &str) -> Result<Separators, Hl7ParseError> {
-        //assuming we have a valid message
-        let mut chars = message.char_indices();
+        Separators::parse_with(message, MissingEncodingChars::Error)
+    }
 
-        if Some((0, 'M')) != chars.next()
-            || Some((1, 'S')) != chars.next()
-            || Some((2, 'H')) != chars.next()
+    /// Create a Separators with the values provided in the message, which must start with `MSH|^~\&` or equivalent for custom separators.
+    /// The `missing` parameter controls what happens when MSH-2 has fewer than the four expected encoding chars (eg `MSH|^~|`),
+    /// either returning an `Err(Hl7ParseError::Msh1Msh2)` or falling back to the default values for the missing chars.
+    /// Non-ASCII separator chars are always rejected with an `Err(Hl7ParseError::Msh1Msh2)`, as the spec requires 7-bit ASCII.
+    ///
+    /// Only the `MSH` prefix is read (at most the first 8 chars), so it's fine to pass the entire message however large it is, there's no need to slice off the first line first.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Hl7ParseError;
+    /// # use rusthl7::{MissingEncodingChars, Separators};
+    /// # fn main() -> Result<(), Hl7ParseError> {
+    /// let delims = Separators::parse_with("MSH|^~|GHH LAB", MissingEncodingChars::UseDefaults)?;
+    /// assert_eq!(delims, Separators::default());
+    ///
+    /// assert!(Separators::parse_with("MSH|^~|GHH LAB", MissingEncodingChars::Error).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn parse_with(
+        message: &str,
+        missing: MissingEncodingChars,
+    ) -> Result<Separators, Hl7ParseError> {
+        // as per `SegmentKind`, a lower case header is accepted
+        if !message
+            .get(..3)
+            .is_some_and(|id| id.eq_ignore_ascii_case("MSH"))
         {
             return Err(Hl7ParseError::Msh1Msh2(
                 "Message doesn't start with 'MSH'".to_string(),
             ));
         }
 
+        let mut chars = message[3..].chars();
+        let field = chars.next().ok_or_else(|| {
+            Hl7ParseError::Msh1Msh2("Message is missing the field separator".to_string())
+        })?;
+
+        // MSH-2 runs up to the next field separator (or end of segment), later versions of the spec may add extra chars which we ignore
+        let encoding: Vec<char> = chars
+            .take_while(|c| *c != field && *c != '\r' && *c != '\n')
+            .take(4)
+            .collect();
+
+        // The spec requires 7-bit ASCII delimiters, and the escape handling relies on each being a single byte
+        if let Some(c) = std::iter::once(&field)
+            .chain(encoding.iter())
+            .find(|c| !c.is_ascii())
+        {
+            return Err(Hl7ParseError::Msh1Msh2(format!(
+                "Separator chars must be ASCII, found '{}'",
+                c
+            )));
+        }
+
+        if encoding.len() < 4 && missing == MissingEncodingChars::Error {
+            return Err(Hl7ParseError::Msh1Msh2(format!(
+                "Expected 4 encoding characters in MSH-2, found {}",
+                encoding.len()
+            )));
+        }
+
         Ok(Separators {
             segment: '\r',
-            field: chars.next().unwrap().1,
-            component: chars.next().unwrap().1,
-            repeat: chars.next().unwrap().1,
-            escape_char: chars.next().unwrap().1,
-            subcomponent: chars.next().unwrap().1,
+            field,
+            component: encoding
+                .first()
+                .copied()
+                .unwrap_or(Separators::DEFAULT.component),
+            repeat: encoding
+                .get(1)
+                .copied()
+                .unwrap_or(Separators::DEFAULT.repeat),
+            escape_char: encoding
+                .get(2)
+                .copied()
+                .unwrap_or(Separators::DEFAULT.escape_char),
+            subcomponent: encoding
+                .get(3)
+                .copied()
+                .unwrap_or(Separators::DEFAULT.subcomponent),
         })
     }
+
+    /// Returns a human readable, labelled description of all six separator chars, useful when debugging messages with custom separators.
+    /// Unlike `Display` (which returns the MSH-2 content) this includes the field and segment separators, with the latter shown escaped.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Separators;
+    /// assert_eq!(
+    ///     Separators::default().describe(),
+    ///     r"field=| component=^ repeat=~ escape=\ subcomponent=& segment=\r"
+    /// );
+    /// ```
+    pub fn describe(&self) -> String {
+        format!(
+            "field={} component={} repeat={} escape={} subcomponent={} segment={}",
+            self.field,
+            self.component,
+            self.repeat,
+            self.escape_char,
+            self.subcomponent,
+            self.segment.escape_default()
+        )
+    }
+
+    /// Returns true if the given char is one of the structural chars (segment, field, repeat, component, subcomponent or escape)
+    /// for this set of separators, and would therefore need escaping to appear in a value.
+    /// ## Example:
+    /// ```
+    /// # use rusthl7::Separators;
+    /// let delims = Separators::default();
+    /// assert!(delims.is_delimiter('|'));
+    /// assert!(!delims.is_delimiter('A'));
+    /// ```
+    pub fn is_delimiter(&self, c: char) -> bool {
+        c == self.segment
+            || c == self.field
+            || c == self.repeat
+            || c == self.component
+            || c == self.subcomponent
+            || c == self.escape_char
+    }
+}
+
+/// Controls how [`Separators::parse_with()`] behaves when MSH-2 contains fewer than the four expected encoding characters.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MissingEncodingChars {
+    /// Return an `Err(Hl7ParseError::Msh1Msh2)`
+    Error,
+    /// Use the default value (see [`Separators::DEFAULT`]) for each missing char
+    UseDefaults,
 }
 
 impl Display for Separators {
@@ -75,7 +191,7 @@ impl Display for Separators {
 }
 
 /// Expects to receive a full message (or at least a MSH segment) in order to parse
-/// out the separator chars.
+/// out the separator chars.  Only the start of the message is read, see [`Separators::parse_with()`].
 impl FromStr for Separators {
     type Err = Hl7ParseError;
 
@@ -88,6 +204,22 @@ impl FromStr for Separators {
 mod tests {
     use super::separators::Separators;
     use super::*;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn ensure_only_the_prefix_is_read() -> Result<(), Hl7ParseError> {
+        // anything after MSH-2 would fail the checks if it was read: non-ASCII chars, a second header with other separators, and garbage
+        let tails = ["GHH LAB|Zoë", "\rMSH#!@*%#", "\u{1F600}\u{0}\u{7F}"];
+        for tail in tails.iter() {
+            let source = format!("MSH|^~\\&|{}", tail);
+            assert_eq!(str::parse::<Separators>(&source)?, Separators::default());
+            assert_eq!(
+                Separators::parse_with(&source, MissingEncodingChars::Error)?,
+                Separators::default()
+            );
+        }
+        Ok(())
+    }
 
     #[test]
     fn ensure_separators_load_correctly() -> Result<(), Hl7ParseError> {
@@ -126,8 +258,90 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn ensure_default_trait_matches_const() {
+        fn make<T: Default>() -> T {
+            T::default()
+        }
+
+        const DELIMS: Separators = Separators::DEFAULT;
+        assert_eq!(make::<Separators>(), DELIMS);
+    }
+
+    #[test]
+    fn ensure_short_encoding_chars_error_by_default() {
+        assert!(Separators::new("MSH|^~|GHH LAB|ELAB-3").is_err());
+        assert!(Separators::new("MSH|^~\\|GHH LAB|ELAB-3").is_err());
+        assert!(Separators::new("MSH").is_err());
+    }
+
+    #[test]
+    fn ensure_short_encoding_chars_can_use_defaults() -> Result<(), Hl7ParseError> {
+        let two = Separators::parse_with("MSH|^~|GHH LAB", MissingEncodingChars::UseDefaults)?;
+        assert_eq!(two, Separators::default());
+
+        let three = Separators::parse_with("MSH|!~#|GHH LAB", MissingEncodingChars::UseDefaults)?;
+        assert_eq!(three.component, '!');
+        assert_eq!(three.repeat, '~');
+        assert_eq!(three.escape_char, '#');
+        assert_eq!(three.subcomponent, '&');
+        Ok(())
+    }
+
     #[test]
     fn ensure_separators_to_string() {
         assert_eq!("^~\\&", Separators::default().to_string());
     }
+
+    #[test]
+    fn ensure_describe_lists_all_separators() -> Result<(), Hl7ParseError> {
+        assert_eq!(
+            Separators::default().describe(),
+            "field=| component=^ repeat=~ escape=\\ subcomponent=& segment=\\r"
+        );
+
+        let custom = Separators::new("MSH#*%$@#GHH LAB")?;
+        assert_eq!(
+            custom.describe(),
+            "field=# component=* repeat=% escape=$ subcomponent=@ segment=\\r"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_multi_byte_separators_are_rejected() {
+        assert!(matches!(
+            Separators::new("MSH|^~§&|GHH LAB"),
+            Err(Hl7ParseError::Msh1Msh2(_))
+        ));
+        assert!(Separators::new("MSH¦^~\\&¦GHH LAB").is_err());
+        assert!(
+            Separators::parse_with("MSH|é|GHH LAB", MissingEncodingChars::UseDefaults).is_err()
+        );
+    }
+
+    #[test]
+    fn ensure_truncated_msh_is_a_clean_error() {
+        for truncated in ["MSH", "MSH|", "MSH|^~\\"].iter() {
+            assert!(
+                matches!(Separators::new(truncated), Err(Hl7ParseError::Msh1Msh2(_))),
+                "expected an error for {:?}",
+                truncated
+            );
+            assert!(matches!(
+                Message::try_from(*truncated),
+                Err(Hl7ParseError::Msh1Msh2(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn ensure_is_delimiter() {
+        let delims = Separators::default();
+        assert!(delims.is_delimiter('^'));
+        assert!(delims.is_delimiter('|'));
+        assert!(delims.is_delimiter('&'));
+        assert!(delims.is_delimiter('\r'));
+        assert!(!delims.is_delimiter('A'));
+    }
 }
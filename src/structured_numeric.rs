@@ -0,0 +1,58 @@
+/// A structured numeric (`SN`) value, as commonly found in OBX-5 for lab results that aren't a simple number (eg `>^100` or `^5^-^10`).
+/// Use [`crate::Field::as_structured_numeric()`] to obtain one.
+///
+/// Missing or non-numeric numbers are `None`, and the comparator and separator are left as empty strings if they weren't sent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StructuredNumeric<'a> {
+    /// One of `>`, `<`, `>=`, `<=`, `=` or `<>`.  An empty comparator should be read as `=`
+    pub comparator: &'a str,
+    /// The (first) number
+    pub num1: Option<f64>,
+    /// Either `-` or `+` for a range, or `/` or `:` for a ratio
+    pub separator: &'a str,
+    /// The second number, if this is a range or ratio
+    pub num2: Option<f64>,
+}
+
+impl<'a> StructuredNumeric<'a> {
+    /// Builds the value from the four `SN` components, any missing components are treated as empty.
+    pub(crate) fn from_components(components: &[&'a str]) -> StructuredNumeric<'a> {
+        let component = |idx: usize| components.get(idx).map(|c| c.trim()).unwrap_or("");
+        let number = |idx: usize| component(idx).parse::<f64>().ok();
+
+        StructuredNumeric {
+            comparator: component(0),
+            num1: number(1),
+            separator: component(2),
+            num2: number(3),
+        }
+    }
+
+    /// Returns true if this value describes a range or ratio (ie has a separator), rather than a single (optionally compared) number.
+    pub fn is_range(&self) -> bool {
+        !self.separator.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ensure_missing_components_are_empty() {
+        let sn = StructuredNumeric::from_components(&["<", "5"]);
+        assert_eq!(sn.comparator, "<");
+        assert_eq!(sn.num1, Some(5.0));
+        assert_eq!(sn.separator, "");
+        assert_eq!(sn.num2, None);
+        assert!(!sn.is_range());
+    }
+
+    #[test]
+    fn ensure_non_numeric_values_are_none() {
+        let sn = StructuredNumeric::from_components(&["", "abc", ":", " 2 "]);
+        assert_eq!(sn.num1, None);
+        assert_eq!(sn.num2, Some(2.0));
+        assert!(sn.is_range());
+    }
+}
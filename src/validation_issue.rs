@@ -0,0 +1,35 @@
+use std::fmt::Display;
+
+/// A non-fatal problem found (and papered over) while leniently parsing a message, see [`crate::Message::parse_lenient()`],
+/// or found when checking a parsed message is well formed, see [`crate::Message::validate()`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ValidationIssue {
+    /// MSH-2 had fewer than the four expected encoding chars, the defaults were used for the missing ones
+    MissingEncodingChars,
+    /// Trailing control chars (eg NUL padding) were found after the last segment and ignored
+    TrailingControlChars,
+    /// A blank line was found between segments and skipped, the value is the (zero based) line number
+    EmptySegment(usize),
+    /// A field the base standard requires (eg MSH-11, the processing id) is missing or empty, the value is the field number
+    MissingMshField(usize),
+}
+
+impl Display for ValidationIssue {
+    /// Required for to_string() and other formatter consumers
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationIssue::MissingEncodingChars => {
+                write!(f, "MSH-2 is missing encoding chars, defaults used")
+            }
+            ValidationIssue::TrailingControlChars => {
+                write!(f, "Trailing control chars ignored")
+            }
+            ValidationIssue::EmptySegment(line) => {
+                write!(f, "Empty segment on line {} skipped", line)
+            }
+            ValidationIssue::MissingMshField(field) => {
+                write!(f, "Required field MSH-{} is missing", field)
+            }
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use crate::{Field, Segment};
+
+/// A read-only visitor over the structure of a [`crate::Message`], driven by [`crate::Message::walk()`].
+///
+/// Every callback has an empty default implementation, so implementors only need to override the levels they care about.
+/// Callbacks are made in document order, with each segment visited before it's fields, and each field before it's components.
+/// Note that the segment identifier (eg `PID`) is _not_ visited as a field.
+/// ## Example:
+/// ```
+/// # use rusthl7::Hl7ParseError;
+/// # use rusthl7::{Field, Message, Visitor};
+/// # use std::convert::TryFrom;
+/// # fn main() -> Result<(), Hl7ParseError> {
+/// struct FieldCounter(usize);
+///
+/// impl<'a> Visitor<'a> for FieldCounter {
+///     fn visit_field(&mut self, _field: &Field<'a>) {
+///         self.0 += 1;
+///     }
+/// }
+///
+/// let m = Message::try_from("MSH|^~\\&|GHH LAB|ELAB-3\rOBR|1|Foo")?;
+/// let mut counter = FieldCounter(0);
+/// m.walk(&mut counter);
+/// assert_eq!(counter.0, 5);
+/// # Ok(())
+/// # }
+/// ```
+pub trait Visitor<'a> {
+    /// Called once for each segment in the message
+    fn visit_segment(&mut self, _segment: &Segment<'a>) {}
+
+    /// Called once for each field in a segment, excluding the segment identifier
+    fn visit_field(&mut self, _field: &Field<'a>) {}
+
+    /// Called once for each component, in each repeat, of a field
+    fn visit_component(&mut self, _component: &'a str) {}
+}
@@ -0,0 +1,112 @@
+use crate::{Hl7ParseError, Segment, Separators};
+use std::convert::TryFrom;
+use std::fmt::Display;
+use std::ops::Deref;
+
+/// A thin wrapper around a [`Segment`] that is known to be a custom (`Z`) segment.
+/// Z-segments carry site-specific data, and this type lets code express that intent (see [`crate::Message::z_segments()`])
+/// while still offering all the usual [`Segment`] functionality (`query()`, `fields` etc) via `Deref`.
+/// ## Example:
+/// ```
+/// # use rusthl7::Hl7ParseError;
+/// # use rusthl7::{Separators, ZSegment};
+/// # fn main() -> Result<(), Hl7ParseError> {
+/// let zpd = ZSegment::parse("ZPD|1|Custom^Data", &Separators::default())?;
+/// assert_eq!("ZPD", zpd.identifier());
+/// assert_eq!("Data", zpd.query("F2.R1.C2"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct ZSegment<'a> {
+    segment: Segment<'a>,
+}
+
+impl<'a> ZSegment<'a> {
+    /// Convert the given line of text into a ZSegment, returning an error if the identifier doesn't start with `Z`.
+    pub fn parse<S: Into<&'a str>>(
+        input: S,
+        delims: &Separators,
+    ) -> Result<ZSegment<'a>, Hl7ParseError> {
+        let segment = Segment::parse(input, delims)?;
+        ZSegment::try_from(segment)
+    }
+
+    /// Returns true if the given segment is a custom (`Z`) segment.  As per [`Segment::kind()`] a lower case `z` is also accepted.
+    #[inline]
+    pub fn is_z_segment(segment: &Segment) -> bool {
+        segment.identifier().starts_with(['Z', 'z'])
+    }
+
+    /// Unwraps this ZSegment back into the underlying generic [`Segment`]
+    pub fn into_inner(self) -> Segment<'a> {
+        self.segment
+    }
+}
+
+impl<'a> TryFrom<Segment<'a>> for ZSegment<'a> {
+    type Error = Hl7ParseError;
+
+    fn try_from(segment: Segment<'a>) -> Result<Self, Self::Error> {
+        if ZSegment::is_z_segment(&segment) {
+            Ok(ZSegment { segment })
+        } else {
+            Err(Hl7ParseError::Generic(format!(
+                "Segment '{}' is not a Z-segment",
+                segment.identifier()
+            )))
+        }
+    }
+}
+
+impl<'a> Deref for ZSegment<'a> {
+    type Target = Segment<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.segment
+    }
+}
+
+impl<'a> Display for ZSegment<'a> {
+    /// Required for to_string() and other formatter consumers.  This returns the source string that represents the segment.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.segment.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Message;
+
+    #[test]
+    fn ensure_z_segments_are_extracted() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rPID|||555-44-4444\rZPD|1|custom^value\rOBR|segment\rZXT|other";
+        let msg = Message::try_from(hl7)?;
+        let z = msg.z_segments();
+
+        assert_eq!(z.len(), 2);
+        assert_eq!(z[0].identifier(), "ZPD");
+        assert_eq!(z[0].query("F2.R1.C2"), "value");
+        assert_eq!(z[1].identifier(), "ZXT");
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_lower_case_z_segments_are_extracted() -> Result<(), Hl7ParseError> {
+        let hl7 = "MSH|^~\\&|GHH LAB|ELAB-3|GHH OE|BLDG4|200202150930||ORU^R01|CNTRL-3456|P|2.4\rzpd|1|custom^value\rOBR|segment";
+        let msg = Message::try_from(hl7)?;
+        let z = msg.z_segments();
+
+        assert_eq!(z.len(), 1);
+        assert_eq!(z[0].identifier(), "zpd");
+        assert!(ZSegment::parse("zxt|other", &Separators::default()).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn ensure_standard_segments_are_rejected() {
+        let result = ZSegment::parse("PID|||555-44-4444", &Separators::default());
+        assert!(result.is_err());
+    }
+}